@@ -0,0 +1,248 @@
+//! JSON-RPC extension for the DPoS pallet.
+//!
+//! The methods forward to the [`DposRuntimeApi`] of the runtime at the requested block, or at the
+//! best block if none is given. Balances are returned as [`NumberOrHex`] so that amounts above
+//! `u64` don't lose precision in JavaScript clients, following `pallet-transaction-payment-rpc`.
+
+use std::{fmt::Display, marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::error::ErrorObject};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{traits::Block as BlockT, Perbill, Percent};
+
+pub use pallet_dpos_runtime_api::DposApi as DposRuntimeApi;
+use pallet_dpos_runtime_api::{CandidateSummary, DelegationSummary, ValidatorSetSummary};
+
+#[cfg(test)]
+mod tests;
+
+/// A candidate of the candidate pool.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCandidate<AccountId> {
+	/// The account ID of the candidate.
+	pub candidate_id: AccountId,
+	/// The bond amount staked by the candidate.
+	pub bond: NumberOrHex,
+	/// The total amount delegated to the candidate.
+	pub total_delegations: NumberOrHex,
+	/// The bond and the total delegated amount of the candidate.
+	pub total_stake: NumberOrHex,
+	/// The number of delegators of the candidate.
+	pub delegator_count: u32,
+	/// The share of each block reward taken by the candidate.
+	pub commission: Perbill,
+	/// Whether the candidate can be selected in the next validator set.
+	pub is_active: bool,
+}
+
+/// A delegation of a delegator.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcDelegation<AccountId> {
+	/// The account ID of the delegated candidate.
+	pub candidate_id: AccountId,
+	/// The amount currently delegated to the candidate.
+	pub amount: NumberOrHex,
+	/// The share of the rewards that is delegated again automatically.
+	pub auto_compound: Percent,
+	/// The amount undelegated from the candidate and waiting for its unbonding period.
+	pub unbonding_amount: NumberOrHex,
+	/// The epoch index from which the unbonding amount can be released, if any.
+	pub unlock_epoch: Option<u32>,
+}
+
+/// A validator of a validator set.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcValidator<AccountId> {
+	/// The account ID of the validator.
+	pub validator_id: AccountId,
+	/// The bond amount staked by the validator.
+	pub bond: NumberOrHex,
+	/// The bond and the total delegated amount of the validator.
+	pub total_stake: NumberOrHex,
+}
+
+/// A validator set and the epoch it is active in.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcValidatorSet<AccountId> {
+	/// The epoch index the validator set is active in.
+	pub epoch_index: u32,
+	/// The validators, from the highest to the lowest stake.
+	pub validators: Vec<RpcValidator<AccountId>>,
+}
+
+#[rpc(client, server)]
+pub trait DposApi<BlockHash, AccountId> {
+	/// The candidates of the candidate pool with their stake.
+	#[method(name = "dpos_candidates")]
+	fn candidates(&self, at: Option<BlockHash>) -> RpcResult<Vec<RpcCandidate<AccountId>>>;
+
+	/// The delegations of a delegator, including the amounts waiting for their unbonding period.
+	#[method(name = "dpos_delegations")]
+	fn delegations(
+		&self,
+		delegator: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<RpcDelegation<AccountId>>>;
+
+	/// The rewards of an account that can be claimed.
+	#[method(name = "dpos_pendingRewards")]
+	fn pending_rewards(&self, account: AccountId, at: Option<BlockHash>)
+		-> RpcResult<NumberOrHex>;
+
+	/// The validator set that would be selected if the epoch ended at the given block.
+	#[method(name = "dpos_nextValidatorSet")]
+	fn next_validator_set(&self, at: Option<BlockHash>) -> RpcResult<RpcValidatorSet<AccountId>>;
+}
+
+/// Provides RPC methods to query the staking state of the DPoS pallet.
+pub struct Dpos<C, Block, Balance> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	_marker: PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> Dpos<C, Block, Balance> {
+	/// Creates a new instance of the DPoS RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+	/// A balance doesn't fit in the `NumberOrHex` representation.
+	BalanceOverflow,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::BalanceOverflow => 2,
+		}
+	}
+}
+
+/// Convert a runtime balance to its RPC representation.
+fn try_into_rpc_balance<Balance>(value: Balance) -> RpcResult<NumberOrHex>
+where
+	Balance: Copy + Display + TryInto<NumberOrHex>,
+{
+	value.try_into().map_err(|_| {
+		ErrorObject::owned(
+			Error::BalanceOverflow.into(),
+			format!("{} doesn't fit in NumberOrHex representation", value),
+			None::<()>,
+		)
+	})
+}
+
+/// Wrap an error of the runtime API.
+fn runtime_error(message: &str, e: impl ToString) -> ErrorObject<'static> {
+	ErrorObject::owned(Error::RuntimeError.into(), message.to_string(), Some(e.to_string()))
+}
+
+impl<C, Block, AccountId, Balance> DposApiServer<<Block as BlockT>::Hash, AccountId>
+	for Dpos<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: DposRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + Copy + Display + TryInto<NumberOrHex> + Send + Sync + 'static,
+{
+	fn candidates(&self, at: Option<Block::Hash>) -> RpcResult<Vec<RpcCandidate<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let candidates = api
+			.candidates(at_hash)
+			.map_err(|e| runtime_error("Unable to query candidates.", e))?;
+		candidates
+			.into_iter()
+			.map(|candidate: CandidateSummary<AccountId, Balance>| {
+				Ok(RpcCandidate {
+					candidate_id: candidate.candidate_id,
+					bond: try_into_rpc_balance(candidate.bond)?,
+					total_delegations: try_into_rpc_balance(candidate.total_delegations)?,
+					total_stake: try_into_rpc_balance(candidate.total_stake)?,
+					delegator_count: candidate.delegator_count,
+					commission: candidate.commission,
+					is_active: candidate.is_active,
+				})
+			})
+			.collect()
+	}
+
+	fn delegations(
+		&self,
+		delegator: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<RpcDelegation<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let delegations = api
+			.delegations(at_hash, delegator)
+			.map_err(|e| runtime_error("Unable to query delegations.", e))?;
+		delegations
+			.into_iter()
+			.map(|delegation: DelegationSummary<AccountId, Balance>| {
+				Ok(RpcDelegation {
+					candidate_id: delegation.candidate_id,
+					amount: try_into_rpc_balance(delegation.amount)?,
+					auto_compound: delegation.auto_compound,
+					unbonding_amount: try_into_rpc_balance(delegation.unbonding_amount)?,
+					unlock_epoch: delegation.unlock_epoch,
+				})
+			})
+			.collect()
+	}
+
+	fn pending_rewards(
+		&self,
+		account: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<NumberOrHex> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let rewards = api
+			.pending_rewards(at_hash, account)
+			.map_err(|e| runtime_error("Unable to query pending rewards.", e))?;
+		try_into_rpc_balance(rewards)
+	}
+
+	fn next_validator_set(
+		&self,
+		at: Option<Block::Hash>,
+	) -> RpcResult<RpcValidatorSet<AccountId>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let ValidatorSetSummary::<AccountId, Balance> { epoch_index, validators } = api
+			.next_validator_set(at_hash)
+			.map_err(|e| runtime_error("Unable to query the next validator set.", e))?;
+		let validators = validators
+			.into_iter()
+			.map(|(validator_id, bond, total_stake)| {
+				Ok(RpcValidator {
+					validator_id,
+					bond: try_into_rpc_balance(bond)?,
+					total_stake: try_into_rpc_balance(total_stake)?,
+				})
+			})
+			.collect::<RpcResult<_>>()?;
+		Ok(RpcValidatorSet { epoch_index, validators })
+	}
+}
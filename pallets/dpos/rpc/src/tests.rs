@@ -0,0 +1,211 @@
+use super::*;
+
+use sp_api::ApiRef;
+use sp_blockchain::{BlockStatus, Info};
+use sp_core::H256;
+use sp_runtime::{
+	generic,
+	traits::{BlakeTwo256, NumberFor},
+	OpaqueExtrinsic,
+};
+
+type AccountId = u64;
+type Balance = u128;
+type Header = generic::Header<u64, BlakeTwo256>;
+type Block = generic::Block<Header, OpaqueExtrinsic>;
+
+const ALICE: AccountId = 1;
+const BOB: AccountId = 2;
+const CHARLIE: AccountId = 3;
+
+/// A client serving the mocked runtime API at any block.
+struct TestClient;
+
+/// The runtime API of the mocked runtime.
+struct TestRuntimeApi;
+
+impl ProvideRuntimeApi<Block> for TestClient {
+	type Api = TestRuntimeApi;
+
+	fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+		TestRuntimeApi.into()
+	}
+}
+
+impl HeaderBackend<Block> for TestClient {
+	fn header(&self, _hash: H256) -> sp_blockchain::Result<Option<Header>> {
+		Ok(None)
+	}
+
+	fn info(&self) -> Info<Block> {
+		Info {
+			best_hash: Default::default(),
+			best_number: 0,
+			genesis_hash: Default::default(),
+			finalized_hash: Default::default(),
+			finalized_number: 0,
+			finalized_state: None,
+			number_leaves: 0,
+			block_gap: None,
+		}
+	}
+
+	fn status(&self, _hash: H256) -> sp_blockchain::Result<BlockStatus> {
+		Ok(BlockStatus::Unknown)
+	}
+
+	fn number(&self, _hash: H256) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+		Ok(None)
+	}
+
+	fn hash(&self, _number: NumberFor<Block>) -> sp_blockchain::Result<Option<H256>> {
+		Ok(None)
+	}
+}
+
+sp_api::mock_impl_runtime_apis! {
+	impl DposRuntimeApi<Block, AccountId, Balance> for TestRuntimeApi {
+		fn candidates() -> Vec<CandidateSummary<AccountId, Balance>> {
+			vec![
+				CandidateSummary {
+					candidate_id: ALICE,
+					bond: 1_000,
+					total_delegations: 500,
+					total_stake: 1_500,
+					delegator_count: 2,
+					commission: Perbill::from_percent(10),
+					is_active: true,
+				},
+				CandidateSummary {
+					candidate_id: BOB,
+					bond: u128::MAX,
+					total_delegations: 0,
+					total_stake: u128::MAX,
+					delegator_count: 0,
+					commission: Perbill::from_percent(0),
+					is_active: false,
+				},
+			]
+		}
+
+		fn delegations(delegator: AccountId) -> Vec<DelegationSummary<AccountId, Balance>> {
+			if delegator != CHARLIE {
+				return vec![];
+			}
+			vec![DelegationSummary {
+				candidate_id: ALICE,
+				amount: 300,
+				auto_compound: Percent::from_percent(50),
+				unbonding_amount: 200,
+				unlock_epoch: Some(4),
+			}]
+		}
+
+		fn pending_rewards(account: AccountId) -> Balance {
+			if account == ALICE { 42 } else { 0 }
+		}
+
+		fn current_validator_set() -> ValidatorSetSummary<AccountId, Balance> {
+			ValidatorSetSummary { epoch_index: 2, validators: vec![(ALICE, 1_000, 1_500)] }
+		}
+
+		fn next_validator_set() -> ValidatorSetSummary<AccountId, Balance> {
+			ValidatorSetSummary {
+				epoch_index: 3,
+				validators: vec![(ALICE, 1_000, 1_500), (BOB, 900, 900)],
+			}
+		}
+	}
+}
+
+fn dpos() -> Dpos<TestClient, Block, Balance> {
+	Dpos::new(Arc::new(TestClient))
+}
+
+#[test]
+fn candidates_are_returned_with_rpc_balances() {
+	let candidates = DposApiServer::candidates(&dpos(), None).unwrap();
+
+	assert_eq!(
+		candidates,
+		vec![
+			RpcCandidate {
+				candidate_id: ALICE,
+				bond: 1_000u128.into(),
+				total_delegations: 500u128.into(),
+				total_stake: 1_500u128.into(),
+				delegator_count: 2,
+				commission: Perbill::from_percent(10),
+				is_active: true,
+			},
+			RpcCandidate {
+				candidate_id: BOB,
+				bond: u128::MAX.into(),
+				total_delegations: 0u128.into(),
+				total_stake: u128::MAX.into(),
+				delegator_count: 0,
+				commission: Perbill::from_percent(0),
+				is_active: false,
+			},
+		]
+	);
+}
+
+#[test]
+fn delegations_include_unbonding_amounts() {
+	let delegations = DposApiServer::delegations(&dpos(), CHARLIE, None).unwrap();
+
+	assert_eq!(
+		delegations,
+		vec![RpcDelegation {
+			candidate_id: ALICE,
+			amount: 300u128.into(),
+			auto_compound: Percent::from_percent(50),
+			unbonding_amount: 200u128.into(),
+			unlock_epoch: Some(4),
+		}]
+	);
+	assert!(DposApiServer::delegations(&dpos(), BOB, None).unwrap().is_empty());
+}
+
+#[test]
+fn pending_rewards_are_returned_at_the_requested_block() {
+	let at = Some(H256::repeat_byte(1));
+
+	assert_eq!(DposApiServer::pending_rewards(&dpos(), ALICE, at).unwrap(), 42u128.into());
+	assert_eq!(DposApiServer::pending_rewards(&dpos(), BOB, at).unwrap(), 0u128.into());
+}
+
+#[test]
+fn next_validator_set_is_returned_with_its_epoch() {
+	let validator_set = DposApiServer::next_validator_set(&dpos(), None).unwrap();
+
+	assert_eq!(
+		validator_set,
+		RpcValidatorSet {
+			epoch_index: 3,
+			validators: vec![
+				RpcValidator {
+					validator_id: ALICE,
+					bond: 1_000u128.into(),
+					total_stake: 1_500u128.into(),
+				},
+				RpcValidator {
+					validator_id: BOB,
+					bond: 900u128.into(),
+					total_stake: 900u128.into(),
+				},
+			],
+		}
+	);
+}
+
+#[test]
+fn responses_are_serialized_in_camel_case() {
+	let validator_set = DposApiServer::next_validator_set(&dpos(), None).unwrap();
+	let json = serde_json::to_value(&validator_set).unwrap();
+
+	assert_eq!(json["epochIndex"], 3);
+	assert_eq!(json["validators"][0]["validatorId"], ALICE);
+	assert!(json["validators"][0].get("totalStake").is_some());
+}
@@ -0,0 +1,104 @@
+//! Runtime API definition for the DPoS pallet.
+//!
+//! The API lets wallets and explorers query the candidates, delegations and rewards of the pallet
+//! without decoding its storage.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{DispatchError, Perbill, Percent, RuntimeDebug};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Query the staking state of the DPoS pallet.
+	pub trait DposApi<AccountId, Balance>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The candidates of the candidate pool with their stake, ordered by account ID.
+		fn candidates() -> Vec<CandidateSummary<AccountId, Balance>>;
+
+		/// The delegations of a delegator, including the ones waiting for their unbonding period.
+		fn delegations(delegator: AccountId) -> Vec<DelegationSummary<AccountId, Balance>>;
+
+		/// The rewards of an account that can be claimed with `claim_reward`.
+		fn pending_rewards(account: AccountId) -> Balance;
+
+		/// The validator set of the current epoch.
+		fn current_validator_set() -> ValidatorSetSummary<AccountId, Balance>;
+
+		/// The validator set that would be selected if the epoch ended now.
+		fn next_validator_set() -> ValidatorSetSummary<AccountId, Balance>;
+
+		/// Simulate a delegation of `amount` from `delegator` to `candidate` and estimate the
+		/// rank of the candidate and the reward of the delegation for one epoch.
+		fn estimate_delegation_reward(
+			delegator: AccountId,
+			candidate: AccountId,
+			amount: Balance,
+		) -> Result<DelegationRewardEstimate<Balance>, DispatchError>;
+	}
+}
+
+/// The `CandidateSummary` struct represents a candidate as returned by the runtime API.
+/// It is generic over the account ID and balance types so that clients can decode it without
+/// knowing the runtime configuration.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, PartialEq, Eq, Clone)]
+pub struct CandidateSummary<AccountId, Balance> {
+	/// The account ID of the candidate.
+	pub candidate_id: AccountId,
+	/// The bond amount staked by the candidate.
+	pub bond: Balance,
+	/// The total amount delegated to the candidate.
+	pub total_delegations: Balance,
+	/// The bond and the total delegated amount of the candidate.
+	pub total_stake: Balance,
+	/// The number of delegators of the candidate.
+	pub delegator_count: u32,
+	/// The share of each block reward taken by the candidate.
+	pub commission: Perbill,
+	/// Whether the candidate can be selected in the next validator set.
+	pub is_active: bool,
+}
+
+/// The `DelegationSummary` struct represents a delegation as returned by the runtime API.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, PartialEq, Eq, Clone)]
+pub struct DelegationSummary<AccountId, Balance> {
+	/// The account ID of the delegated candidate.
+	pub candidate_id: AccountId,
+	/// The amount currently delegated to the candidate.
+	pub amount: Balance,
+	/// The share of the rewards that is delegated again automatically.
+	pub auto_compound: Percent,
+	/// The amount undelegated from the candidate and waiting for its unbonding period.
+	pub unbonding_amount: Balance,
+	/// The epoch index from which the unbonding amount can be released, if any.
+	pub unlock_epoch: Option<u32>,
+}
+
+/// The `ValidatorSetSummary` struct represents a validator set as returned by the runtime API.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, PartialEq, Eq, Clone)]
+pub struct ValidatorSetSummary<AccountId, Balance> {
+	/// The epoch index the validator set is active in.
+	pub epoch_index: u32,
+	/// The validators with their bond and total stake, from the highest to the lowest stake.
+	pub validators: Vec<(AccountId, Balance, Balance)>,
+}
+
+/// The `DelegationRewardEstimate` struct represents the outcome of a simulated delegation as
+/// returned by the runtime API.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, PartialEq, Eq, Clone)]
+pub struct DelegationRewardEstimate<Balance> {
+	/// Whether the candidate would be selected in the next validator set.
+	pub is_active_validator: bool,
+	/// The position of the candidate when ranked by total stake, starting from 1.
+	pub rank: u32,
+	/// The total stake of the candidate after the delegation.
+	pub total_stake: Balance,
+	/// The amount delegated by the delegator to the candidate after the delegation.
+	pub delegated_amount: Balance,
+	/// The reward expected by the delegator for one epoch as a validator, before any
+	/// auto-compounding. Zero if the candidate would not be selected.
+	pub epoch_reward: Balance,
+}
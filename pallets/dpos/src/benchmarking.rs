@@ -4,22 +4,581 @@ use super::*;
 
 #[allow(unused)]
 use crate::Pallet as Dpos;
+use crate::models::{DelegatorIndex, ElectionPhase, OffenceKind, UnappliedSlash};
 use frame_benchmarking::v2::*;
-use frame_system::RawOrigin;
+use frame_support::{
+	pallet_prelude::*,
+	traits::fungible::{Inspect, Mutate},
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use sp_runtime::{
+	traits::{Bounded, One, Zero},
+	Perbill, Percent, Saturating,
+};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+/// The smallest bond accepted by `register_as_candidate`.
+fn min_bond<T: Config>() -> BalanceOf<T> {
+	T::MinCandidateBond::get().max(One::one())
+}
+
+/// The smallest amount accepted by `delegate`.
+fn min_delegation<T: Config>() -> BalanceOf<T> {
+	T::MinDelegateAmount::get().max(One::one())
+}
+
+/// Creates an account with enough funds to bond and delegate in every benchmark.
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let account: T::AccountId = account(name, index, SEED);
+	let balance = BalanceOf::<T>::max_value() / u32::MAX.into();
+	let _ = T::NativeBalance::set_balance(&account, balance);
+	account
+}
+
+/// Registers a new funded candidate bonding `bond`.
+fn register_candidate<T: Config>(index: u32, bond: BalanceOf<T>) -> T::AccountId {
+	let candidate = funded_account::<T>("candidate", index);
+	Dpos::<T>::register_as_candidate(
+		RawOrigin::Signed(candidate.clone()).into(),
+		bond,
+		Perbill::zero(),
+	)
+	.expect("Candidate registration must succeed in benchmarks");
+	candidate
+}
+
+/// Delegates to `candidate` from `count` new funded delegators. The delegated amounts increase
+/// with the delegator index, so the first delegator is always the lowest one.
+fn delegate_to<T: Config>(candidate: &T::AccountId, count: u32) -> Vec<T::AccountId> {
+	(0..count)
+		.map(|index| {
+			let delegator = funded_account::<T>("delegator", index);
+			Dpos::<T>::delegate(
+				RawOrigin::Signed(delegator.clone()).into(),
+				candidate.clone(),
+				min_delegation::<T>().saturating_add(index.into()),
+			)
+			.expect("Delegation must succeed in benchmarks");
+			delegator
+		})
+		.collect()
+}
+
+/// Sets a profile with every field at its maximum length, so that the deposit is the highest.
+fn set_max_profile<T: Config>(candidate: &T::AccountId) {
+	let field = vec![b'x'; T::MaxProfileFieldLength::get() as usize];
+	Dpos::<T>::set_candidate_profile(
+		RawOrigin::Signed(candidate.clone()).into(),
+		field.clone(),
+		field.clone(),
+		field.clone(),
+		field,
+	)
+	.expect("Profile must be accepted in benchmarks");
+}
+
+/// Fills the state that the epoch boundary pays out or clears for the validators of the ending
+/// epoch: their exposures, one authored block each so that an epoch budget is split between all
+/// of them and their delegators, and a report of every offence kind. The epoch budget is only
+/// paid out when the `RewardMode` of the runtime is `EpochBudget`.
+fn fill_ending_epoch<T: Config>(validator_set: &TopCandidateVec<T>) {
+	let epoch_index = EpochIndex::<T>::get();
+	CurrentValidators::<T>::put(
+		BoundedVec::try_from(validator_set.clone())
+			.expect("The validator set must fit in the active set"),
+	);
+	Dpos::<T>::capture_exposures(epoch_index, validator_set);
+	for (validator, _, _) in validator_set.iter() {
+		AuthoredBlocks::<T>::insert(validator, 1);
+		for kind in [OffenceKind::Equivocation, OffenceKind::Unresponsiveness] {
+			OffenceReports::<T>::insert(epoch_index, (validator.clone(), kind), ());
+		}
+	}
+}
+
+/// Moves the epoch index past the unbonding period of the requests made in the current epoch.
+fn skip_unbonding_period<T: Config>() {
+	EpochIndex::<T>::mutate(|epoch_index| {
+		*epoch_index = epoch_index.saturating_add(T::UnbondingEpochs::get())
+	});
+}
 
 #[benchmarks]
 mod benchmarks {
 	use super::*;
 
 	#[benchmark]
-	fn do_something() {
-		let value = 100u32.into();
-		let caller: T::AccountId = whitelisted_caller();
+	fn force_report_new_validators(v: Linear<0, { T::MaxValidators::get() - 1 }>) {
+		let new_set: Vec<T::AccountId> =
+			(0..v).map(|index| account("validator", index, SEED)).collect();
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, new_set);
+	}
+
+	#[benchmark]
+	fn register_as_candidate(c: Linear<0, { T::MaxCandidates::get() - 1 }>) {
+		for index in 0..c {
+			register_candidate::<T>(index, min_bond::<T>());
+		}
+		let caller = funded_account::<T>("caller", 0);
+		let initial_bond = min_bond::<T>();
+		let commission = T::MaxCommission::get();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), initial_bond, commission);
+
+		assert_last_event::<T>(
+			Event::CandidateRegistered { candidate_id: caller, initial_bond, commission }.into(),
+		);
+	}
+
+	#[benchmark]
+	fn delegate(d: Linear<0, { T::MaxCandidateDelegators::get() }>) {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		delegate_to::<T>(&candidate, d);
+		let caller = funded_account::<T>("caller", 0);
+		// Higher than every existing delegation, so the lowest delegator is kicked when full
+		let amount = min_delegation::<T>().saturating_add(d.into());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), candidate.clone(), amount);
+
+		assert!(DelegationInfos::<T>::contains_key(&caller, &candidate));
+	}
+
+	#[benchmark]
+	fn unregister_as_candidate(
+		d: Linear<0, { T::MaxCandidateDelegators::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		delegate_to::<T>(&candidate, d);
+		set_max_profile::<T>(&candidate);
+		let origin =
+			T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, candidate.clone());
+
+		assert!(!Dpos::<T>::is_candidate(&candidate));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn undelegate(
+		d: Linear<1, { T::MaxCandidateDelegators::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		let delegator = delegate_to::<T>(&candidate, d).remove(0);
+		let origin =
+			T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, delegator.clone(), candidate.clone(), min_delegation::<T>());
+
+		assert!(!DelegationInfos::<T>::contains_key(&delegator, &candidate));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn claim_reward() {
+		let caller = funded_account::<T>("caller", 0);
+		// The reward pot pays a part of the reward, the rest is minted
+		let from_pot = T::NativeBalance::minimum_balance();
+		let _ = T::NativeBalance::set_balance(&Dpos::<T>::reward_pot_account(), from_pot);
+		let total_reward = min_bond::<T>().saturating_add(from_pot);
+		Rewards::<T>::insert(&caller, total_reward);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()));
+
+		assert_last_event::<T>(Event::RewardClaimed { claimer: caller, total_reward }.into());
+	}
+
+	#[benchmark]
+	fn leave_candidate_pool() {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(candidate.clone()));
+
+		assert!(!Dpos::<T>::get_candidate(&candidate).expect("Candidate exists").is_active());
+	}
+
+	#[benchmark]
+	fn execute_leave_candidates(d: Linear<0, { T::MaxCandidateDelegators::get() }>) {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		delegate_to::<T>(&candidate, d);
+		set_max_profile::<T>(&candidate);
+		Dpos::<T>::leave_candidate_pool(RawOrigin::Signed(candidate.clone()).into())
+			.expect("Candidate can leave");
+		skip_unbonding_period::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(candidate.clone()), candidate.clone());
+
+		assert!(!Dpos::<T>::is_candidate(&candidate));
+	}
+
+	#[benchmark]
+	fn schedule_undelegate(d: Linear<1, { T::MaxCandidateDelegators::get() }>) {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		let delegator = delegate_to::<T>(&candidate, d).remove(0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(delegator.clone()), candidate, min_delegation::<T>());
+
+		assert_eq!(UnbondingDelegations::<T>::get(&delegator).len(), 1);
+	}
+
+	#[benchmark]
+	fn execute_undelegate(u: Linear<1, { T::MaxDelegateCount::get() }>) {
+		let delegator = funded_account::<T>("delegator", 0);
+		for index in 0..u {
+			let candidate = register_candidate::<T>(index, min_bond::<T>());
+			Dpos::<T>::delegate(
+				RawOrigin::Signed(delegator.clone()).into(),
+				candidate.clone(),
+				min_delegation::<T>(),
+			)
+			.expect("Delegation must succeed in benchmarks");
+			Dpos::<T>::schedule_undelegate(
+				RawOrigin::Signed(delegator.clone()).into(),
+				candidate,
+				min_delegation::<T>(),
+			)
+			.expect("Undelegation can be scheduled");
+		}
+		skip_unbonding_period::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(delegator.clone()));
+
+		assert!(!UnbondingDelegations::<T>::contains_key(&delegator));
+	}
+
+	#[benchmark]
+	fn cancel_undelegate(d: Linear<1, { T::MaxCandidateDelegators::get() }>) {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		let delegator = delegate_to::<T>(&candidate, d).remove(0);
+		Dpos::<T>::schedule_undelegate(
+			RawOrigin::Signed(delegator.clone()).into(),
+			candidate.clone(),
+			min_delegation::<T>(),
+		)
+		.expect("Undelegation can be scheduled");
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(delegator.clone()), candidate.clone());
+
+		assert!(DelegationInfos::<T>::contains_key(&delegator, &candidate));
+	}
+
+	#[benchmark]
+	fn candidate_bond_more() {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		let amount = min_bond::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(candidate.clone()), amount);
+
+		assert_last_event::<T>(
+			Event::CandidateBondIncreased {
+				candidate_id: candidate,
+				amount,
+				new_bond: min_bond::<T>().saturating_add(amount),
+			}
+			.into(),
+		);
+	}
+
+	#[benchmark]
+	fn schedule_candidate_bond_less() {
+		let initial_bond = min_bond::<T>().saturating_mul(2u32.into());
+		let candidate = register_candidate::<T>(0, initial_bond);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(candidate.clone()), min_bond::<T>());
+
+		assert!(CandidateBondLessRequests::<T>::contains_key(&candidate));
+	}
+
+	#[benchmark]
+	fn execute_candidate_bond_less() {
+		let initial_bond = min_bond::<T>().saturating_mul(2u32.into());
+		let candidate = register_candidate::<T>(0, initial_bond);
+		Dpos::<T>::schedule_candidate_bond_less(
+			RawOrigin::Signed(candidate.clone()).into(),
+			min_bond::<T>(),
+		)
+		.expect("Bond decrease can be scheduled");
+		skip_unbonding_period::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(candidate.clone()));
+
+		assert!(!CandidateBondLessRequests::<T>::contains_key(&candidate));
+	}
+
+	#[benchmark]
+	fn cancel_candidate_bond_less() {
+		let initial_bond = min_bond::<T>().saturating_mul(2u32.into());
+		let candidate = register_candidate::<T>(0, initial_bond);
+		Dpos::<T>::schedule_candidate_bond_less(
+			RawOrigin::Signed(candidate.clone()).into(),
+			min_bond::<T>(),
+		)
+		.expect("Bond decrease can be scheduled");
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(candidate.clone()));
+
+		let candidate_detail = Dpos::<T>::get_candidate(&candidate).expect("Candidate exists");
+		assert_eq!(candidate_detail.bond, initial_bond);
+	}
+
+	#[benchmark]
+	fn set_auto_compound() {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		let delegator = delegate_to::<T>(&candidate, 1).remove(0);
+		let value = Percent::from_percent(50);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(delegator.clone()), candidate.clone(), value);
+
+		assert_last_event::<T>(
+			Event::AutoCompoundSet { candidate_id: candidate, delegator, value }.into(),
+		);
+	}
+
+	#[benchmark]
+	fn set_commission() {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		EpochIndex::<T>::put(T::CommissionUpdateEpochs::get());
+		let commission = T::MaxCommission::get();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(candidate.clone()), commission);
+
+		assert_last_event::<T>(
+			Event::CommissionUpdated { candidate_id: candidate, commission }.into(),
+		);
+	}
+
+	#[benchmark]
+	fn set_candidate_profile() {
+		let candidate = register_candidate::<T>(0, min_bond::<T>());
+		let field = vec![b'x'; T::MaxProfileFieldLength::get() as usize];
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(candidate.clone()),
+			field.clone(),
+			field.clone(),
+			field.clone(),
+			field,
+		);
+
+		assert!(CandidateProfiles::<T>::contains_key(&candidate));
+	}
+
+	#[benchmark]
+	fn cancel_deferred_slash(
+		s: Linear<1, { T::MaxUnappliedSlashes::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let epoch_index = EpochIndex::<T>::get();
+		// Every slash carries a full list of delegators, so that decoding is the most expensive
+		let others: BoundedVec<_, T::MaxCandidateDelegators> = BoundedVec::truncate_from(
+			(0..T::MaxCandidateDelegators::get())
+				.map(|index| (account("delegator", index, SEED), min_delegation::<T>()))
+				.collect(),
+		);
+		let slashes = (0..s)
+			.map(|index| UnappliedSlash {
+				validator: account("validator", index, SEED),
+				kind: OffenceKind::Equivocation,
+				slash_fraction: Perbill::from_percent(10),
+				own: min_bond::<T>(),
+				others: others.clone(),
+			})
+			.collect::<Vec<UnappliedSlash<T>>>();
+		UnappliedSlashes::<T>::insert(epoch_index, BoundedVec::truncate_from(slashes));
+		let origin =
+			T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
 		#[extrinsic_call]
-		do_something(RawOrigin::Signed(caller), value);
+		_(origin as T::RuntimeOrigin, epoch_index, (0..s).collect::<Vec<u32>>());
+
+		assert!(!UnappliedSlashes::<T>::contains_key(epoch_index));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn on_initialize_epoch_transition(
+		c: Linear<1, { T::MaxCandidates::get() }>,
+		d: Linear<0, { T::MaxCandidateDelegators::get() }>,
+	) {
+		for index in 0..c {
+			// Distinct bonds so that sorting the candidates is not trivial
+			let bond = min_bond::<T>().saturating_add(index.into());
+			let candidate = register_candidate::<T>(index, bond);
+			// The delegators index is written directly, only the snapshot reads it
+			let mut delegators = DelegatorIndex::<T>::default();
+			let mut total_delegated = BalanceOf::<T>::zero();
+			for delegator_index in 0..d {
+				let delegator = account("delegator", index * d + delegator_index, SEED);
+				let amount = min_delegation::<T>().saturating_add(delegator_index.into());
+				delegators.insert(delegator, amount);
+				total_delegated = total_delegated.saturating_add(amount);
+			}
+			CandidateDelegators::<T>::insert(&candidate, delegators);
+			CandidatePool::<T>::mutate(&candidate, |candidate_detail| {
+				if let Some(candidate_detail) = candidate_detail {
+					candidate_detail.sum_delegation = total_delegated;
+				}
+			});
+		}
+		fill_ending_epoch::<T>(&Dpos::<T>::select_validator_set());
+		let epoch_index = EpochIndex::<T>::get();
+		let block_number: BlockNumberFor<T> = T::EpochDuration::get();
+
+		#[block]
+		{
+			Dpos::<T>::on_initialize(block_number);
+		}
+
+		assert_eq!(EpochIndex::<T>::get(), epoch_index + 1);
+	}
+
+	#[benchmark]
+	fn on_initialize_election_page(p: Linear<1, { T::ElectionPageSize::get() }>) {
+		// Enough full validators to capture `p` delegators in the exposures phase
+		let delegators_per_validator = T::MaxCandidateDelegators::get().max(1);
+		let validator_count = p / delegators_per_validator + 1;
+		for index in 0..validator_count {
+			let candidate = register_candidate::<T>(index, min_bond::<T>());
+			let mut delegators = DelegatorIndex::<T>::default();
+			for delegator_index in 0..delegators_per_validator {
+				let delegator =
+					account("delegator", index * delegators_per_validator + delegator_index, SEED);
+				delegators.insert(delegator, min_delegation::<T>());
+			}
+			CandidateDelegators::<T>::insert(&candidate, delegators);
+		}
+		Dpos::<T>::start_election();
+		// Collect the candidates beforehand, only the exposures phase pages through delegators
+		Dpos::<T>::process_election_page(validator_count);
+		let processed;
+
+		#[block]
+		{
+			processed = Dpos::<T>::process_election_page(p);
+		}
+
+		assert!(processed >= p || ElectionStatus::<T>::get() == ElectionPhase::Ready);
+	}
+
+	#[benchmark]
+	fn on_initialize_slash_page(p: Linear<1, { T::SlashPageSize::get() }>) {
+		// Enough due slashes of fully delegated validators to process `p` items
+		let delegators_per_slash = T::MaxCandidateDelegators::get();
+		let slash_count =
+			(p / delegators_per_slash.saturating_add(1) + 1).min(T::MaxUnappliedSlashes::get());
+		let slashes = (0..slash_count)
+			.map(|index| {
+				let validator = register_candidate::<T>(index, min_bond::<T>());
+				let others = delegate_to::<T>(&validator, delegators_per_slash)
+					.into_iter()
+					.map(|delegator| (delegator, min_delegation::<T>()))
+					.collect::<Vec<_>>();
+				UnappliedSlash {
+					validator,
+					kind: OffenceKind::Equivocation,
+					slash_fraction: Perbill::from_percent(10),
+					own: min_bond::<T>(),
+					others: BoundedVec::truncate_from(others),
+				}
+			})
+			.collect::<Vec<UnappliedSlash<T>>>();
+		let slash_epoch = EpochIndex::<T>::get();
+		UnappliedSlashes::<T>::insert(slash_epoch, BoundedVec::truncate_from(slashes));
+		NextSlashEpoch::<T>::put(slash_epoch);
+		EpochIndex::<T>::put(slash_epoch.saturating_add(T::SlashDeferEpochs::get()));
+		let processed;
+
+		#[block]
+		{
+			processed = Dpos::<T>::apply_deferred_slashes(p);
+		}
+
+		assert!(processed >= p || !UnappliedSlashes::<T>::contains_key(slash_epoch));
+	}
+
+	#[benchmark]
+	fn on_initialize_block_reward(d: Linear<0, { T::MaxCandidateDelegators::get() }>) {
+		// Every delegator of the block author compounds its whole reward
+		let validator = register_candidate::<T>(0, min_bond::<T>());
+		for delegator in delegate_to::<T>(&validator, d) {
+			Dpos::<T>::set_auto_compound(
+				RawOrigin::Signed(delegator).into(),
+				validator.clone(),
+				Percent::from_percent(100),
+			)
+			.expect("Auto-compounding must be set in benchmarks");
+		}
+		let epoch_index = EpochIndex::<T>::get();
+		Dpos::<T>::capture_exposures(epoch_index, &Dpos::<T>::select_validator_set());
+		let exposure =
+			EpochExposures::<T>::get(epoch_index, &validator).expect("The validator is exposed");
+		let total_delegated =
+			Dpos::<T>::get_candidate(&validator).expect("Candidate exists").sum_delegation;
+
+		#[block]
+		{
+			Dpos::<T>::distribute_block_reward(epoch_index, &validator, &exposure, exposure.total);
+		}
+
+		let candidate = Dpos::<T>::get_candidate(&validator).expect("Candidate exists");
+		assert!(d == 0 || candidate.sum_delegation > total_delegated);
+	}
+
+	#[benchmark]
+	fn on_initialize_epoch_swap(
+		v: Linear<1, { T::MaxValidators::get() }>,
+		d: Linear<0, { T::MaxCandidateDelegators::get() }>,
+	) {
+		for index in 0..v {
+			let candidate = register_candidate::<T>(index, min_bond::<T>());
+			let mut delegators = DelegatorIndex::<T>::default();
+			for delegator_index in 0..d {
+				let delegator = account("delegator", index * d + delegator_index, SEED);
+				delegators.insert(delegator, min_delegation::<T>());
+			}
+			CandidateDelegators::<T>::insert(&candidate, delegators);
+		}
+		fill_ending_epoch::<T>(&Dpos::<T>::select_validator_set());
+		// Complete the election, so that the boundary only swaps the staged data
+		Dpos::<T>::start_election();
+		while ElectionStatus::<T>::get() != ElectionPhase::Ready {
+			Dpos::<T>::process_election_page(u32::MAX);
+		}
+		let epoch_index = EpochIndex::<T>::get();
+		let block_number: BlockNumberFor<T> = T::EpochDuration::get();
+
+		#[block]
+		{
+			Dpos::<T>::on_initialize(block_number);
+		}
 
-		assert_eq!(Something::<T>::get(), Some(value));
+		assert_eq!(EpochIndex::<T>::get(), epoch_index + 1);
+		assert_eq!(ElectionStatus::<T>::get(), ElectionPhase::Idle);
 	}
 
 	impl_benchmark_test_suite!(Dpos, crate::mock::new_test_ext(), crate::mock::Test);
-}
\ No newline at end of file
+}
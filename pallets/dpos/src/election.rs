@@ -0,0 +1,123 @@
+//! Multi-block election of the next validator set.
+//!
+//! When [`Config::ElectionLookahead`] is not zero, the election starts `ElectionLookahead`
+//! blocks before the epoch boundary. Each block then processes a page of at most
+//! [`Config::ElectionPageSize`] items, where an item is a candidate of the [`CandidatePool`], a
+//! validator whose exposure is captured or one of its delegators:
+//!
+//! 1. The active candidates are collected in [`ElectionCandidates`], resuming from the last
+//!    collected candidate stored in [`ElectionStatus`].
+//! 2. Once the pool is exhausted, the candidates are ranked and truncated to the validator set.
+//! 3. The exposures of the validators are captured in [`EpochExposures`] and
+//!    [`EpochExposurePages`] under the next epoch index, resuming from the validator position
+//!    stored in [`ElectionStatus`]. All the delegators of a validator are captured at once, so
+//!    that its exposure is consistent.
+//!
+//! At the epoch boundary, a complete election replaces [`CurrentValidators`] and the next epoch
+//! starts reading the exposures it captured. If the election did not complete in time, it is
+//! dropped and the validator set is elected within the boundary block as when the lookahead is
+//! zero.
+//!
+//! Changes to the pool after a candidate or a validator was collected are only taken into
+//! account from the next election.
+use crate::{models::*, pallet::*};
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::traits::{Saturating, Zero},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+impl<T: Config> Pallet<T> {
+	/// Whether the multi-block election of the next validator set starts at block `n`.
+	pub(crate) fn election_starts_at(n: BlockNumberFor<T>) -> bool {
+		let lookahead = T::ElectionLookahead::get();
+		!lookahead.is_zero() && (n.saturating_add(lookahead) % T::EpochDuration::get()).is_zero()
+	}
+
+	/// Start a new election, dropping anything staged by a previous one.
+	pub(crate) fn start_election() {
+		ElectionCandidates::<T>::kill();
+		Self::clear_exposures(EpochIndex::<T>::get().saturating_add(1));
+		ElectionStatus::<T>::put(ElectionPhase::Candidates { last: None });
+	}
+
+	/// Process up to `budget` items of the running election. Returns the number of processed
+	/// items, which exceeds `budget` by at most `MaxCandidateDelegators` when the last captured
+	/// validator does not fit in the page.
+	pub(crate) fn process_election_page(budget: u32) -> u32 {
+		let mut processed = 0u32;
+		while processed < budget {
+			let step = match ElectionStatus::<T>::get() {
+				ElectionPhase::Idle | ElectionPhase::Ready => break,
+				ElectionPhase::Candidates { last } =>
+					Self::collect_candidates(last, budget.saturating_sub(processed)),
+				ElectionPhase::Exposures { validator } => Self::capture_next_exposure(validator),
+			};
+			processed = processed.saturating_add(step);
+		}
+		processed
+	}
+
+	/// Take the validator set of a complete election, if any, and reset the election state. The
+	/// exposures of the validator set are already captured for the next epoch.
+	pub(crate) fn take_elected() -> Option<TopCandidateVec<T>> {
+		let is_ready = ElectionStatus::<T>::take() == ElectionPhase::Ready;
+		let validator_set = ElectionCandidates::<T>::take().into_inner();
+		is_ready.then_some(validator_set)
+	}
+
+	/// Collect up to `budget` active candidates after `last`. Returns the number of visited
+	/// candidates, or one when the pool is exhausted and the candidates are ranked.
+	fn collect_candidates(last: Option<T::AccountId>, budget: u32) -> u32 {
+		let mut candidates = ElectionCandidates::<T>::get();
+		let iter = match &last {
+			Some(last) => CandidatePool::<T>::iter_from(CandidatePool::<T>::hashed_key_for(last)),
+			None => CandidatePool::<T>::iter(),
+		};
+
+		let mut visited = 0u32;
+		let mut last = last;
+		for (candidate_id, candidate) in iter {
+			if visited == budget {
+				ElectionCandidates::<T>::put(candidates);
+				ElectionStatus::<T>::put(ElectionPhase::Candidates { last });
+				return visited;
+			}
+			visited.saturating_inc();
+			if candidate.is_active() {
+				// The candidate pool is bounded by `MaxCandidates` as well
+				let stake = (candidate_id.clone(), candidate.bond, candidate.total());
+				let _ = candidates.try_push(stake);
+			}
+			last = Some(candidate_id);
+		}
+
+		// The pool is exhausted, keep the top candidates as the next validator set
+		let mut ranked = candidates.into_inner();
+		if (ranked.len() as u32) < T::MinValidators::get() {
+			ranked.clear();
+		}
+		Self::rank_candidates(&mut ranked);
+		ranked.truncate(T::MaxValidators::get() as usize);
+		ElectionCandidates::<T>::put(BoundedVec::truncate_from(ranked));
+		ElectionStatus::<T>::put(ElectionPhase::Exposures { validator: 0 });
+		visited.max(1)
+	}
+
+	/// Capture the exposure of the validator at position `validator` for the next epoch. Returns
+	/// the number of processed items.
+	fn capture_next_exposure(validator: u32) -> u32 {
+		let Some((validator_id, bond, _)) =
+			ElectionCandidates::<T>::get().get(validator as usize).cloned()
+		else {
+			ElectionStatus::<T>::put(ElectionPhase::Ready);
+			return 1;
+		};
+
+		let next_epoch = EpochIndex::<T>::get().saturating_add(1);
+		let delegator_count = Self::capture_exposure(next_epoch, &validator_id, bond);
+		let validator = validator.saturating_add(1);
+		ElectionStatus::<T>::put(ElectionPhase::Exposures { validator });
+		delegator_count.saturating_add(1)
+	}
+}
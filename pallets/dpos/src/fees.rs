@@ -0,0 +1,47 @@
+//! Redistribution of transaction fees to the block author.
+//!
+//! [`DealWithFees`] can be used as the `OnUnbalanced` handler of the runtime's transaction payment
+//! adapter. Fees and tips are added to the reward pot of the current block author after the
+//! [`Config::TreasuryCut`] is sent to [`Config::TreasuryAccount`].
+//!
+//! The author's share of the fees is already issued, so it is kept by the reward pot account
+//! derived from [`Config::PalletId`] and `claim_reward` pays it from there instead of minting it.
+//! The total issuance is therefore unchanged by the fees until they are claimed.
+use crate::pallet::*;
+use frame_support::traits::{
+	fungible::{Balanced, Credit},
+	Get, Imbalance, OnUnbalanced,
+};
+use sp_std::marker::PhantomData;
+
+/// The credit of the native balance of the pallet.
+pub type CreditOf<T> =
+	Credit<<T as frame_system::Config>::AccountId, <T as Config>::NativeBalance>;
+
+/// Route transaction fees and tips to the reward pot of the current block author.
+pub struct DealWithFees<T>(PhantomData<T>);
+
+impl<T: Config> OnUnbalanced<CreditOf<T>> for DealWithFees<T> {
+	fn on_nonzero_unbalanced(fees: CreditOf<T>) {
+		Pallet::<T>::reward_fees(fees);
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Split collected fees between the treasury and the reward pot of the block author. Fees
+	/// are burnt if the block author is unknown, or if the reward pot account can't receive them
+	/// because it doesn't exist yet and they are below the existential deposit.
+	pub(crate) fn reward_fees(fees: CreditOf<T>) {
+		let treasury_cut = T::TreasuryCut::get() * fees.peek();
+		let (to_treasury, to_author) = fees.split(treasury_cut);
+		// If the treasury can't receive the funds, the returned credit is dropped and burnt
+		let _ = T::NativeBalance::resolve(&T::TreasuryAccount::get(), to_treasury);
+
+		if let Some(current_block_author) = Self::find_author() {
+			let amount = to_author.peek();
+			if T::NativeBalance::resolve(&Self::reward_pot_account(), to_author).is_ok() {
+				Self::add_reward(&current_block_author, amount);
+			}
+		}
+	}
+}
@@ -0,0 +1,140 @@
+//! Randomized tests of the staking accounting.
+//!
+//! Each case builds the mock runtime, applies a random sequence of calls and block advances, and
+//! checks after every step that the recorded stake agrees with the delegations and the holds. The
+//! calls are expected to fail often, a failing call must leave the accounting untouched as well.
+use crate::{mock::*, *};
+use frame_support::traits::fungible::InspectHold;
+use proptest::prelude::*;
+use sp_runtime::Perbill;
+
+/// The accounts used by the generated actions, all funded at genesis.
+const ACCOUNTS: core::ops::RangeInclusive<AccountId> = 1..=12;
+
+/// A step of a generated sequence.
+#[derive(Clone, Debug)]
+enum Action {
+	RegisterAsCandidate { candidate: AccountId, bond: Balance },
+	Delegate { delegator: AccountId, candidate: AccountId, amount: Balance },
+	Undelegate { delegator: AccountId, candidate: AccountId, amount: Balance },
+	UnregisterAsCandidate { candidate: AccountId },
+	ClaimReward { who: AccountId },
+	AdvanceBlocks { blocks: BlockNumber, author: Option<AccountId> },
+}
+
+fn account() -> impl Strategy<Value = AccountId> {
+	ACCOUNTS
+}
+
+fn action() -> impl Strategy<Value = Action> {
+	// The amounts go below the minimums and above the free balances to exercise the errors
+	prop_oneof![
+		1 => (account(), 0..3_000u128)
+			.prop_map(|(candidate, bond)| Action::RegisterAsCandidate { candidate, bond }),
+		3 => (account(), account(), 0..3_000u128).prop_map(|(delegator, candidate, amount)| {
+			Action::Delegate { delegator, candidate, amount }
+		}),
+		2 => (account(), account(), 0..1_500u128).prop_map(|(delegator, candidate, amount)| {
+			Action::Undelegate { delegator, candidate, amount }
+		}),
+		1 => account().prop_map(|candidate| Action::UnregisterAsCandidate { candidate }),
+		1 => account().prop_map(|who| Action::ClaimReward { who }),
+		2 => (1..15u64, proptest::option::of(account()))
+			.prop_map(|(blocks, author)| Action::AdvanceBlocks { blocks, author }),
+	]
+}
+
+fn apply(action: Action) {
+	// The result is ignored, the invariants must hold whether the call succeeds or not
+	let _ = match action {
+		Action::RegisterAsCandidate { candidate, bond } => Dpos::register_as_candidate(
+			RuntimeOrigin::signed(candidate),
+			bond,
+			Perbill::zero(),
+		),
+		Action::Delegate { delegator, candidate, amount } =>
+			Dpos::delegate(RuntimeOrigin::signed(delegator), candidate, amount),
+		Action::Undelegate { delegator, candidate, amount } =>
+			Dpos::undelegate(RuntimeOrigin::root(), delegator, candidate, amount),
+		Action::UnregisterAsCandidate { candidate } =>
+			Dpos::unregister_as_candidate(RuntimeOrigin::root(), candidate),
+		Action::ClaimReward { who } => Dpos::claim_reward(RuntimeOrigin::signed(who)),
+		Action::AdvanceBlocks { blocks, author } => {
+			BlockAuthor::set(author);
+			run_to_block(System::block_number() + blocks);
+			Ok(())
+		},
+	};
+}
+
+fn held(reason: HoldReason, who: AccountId) -> Balance {
+	Balances::balance_on_hold(&reason.into(), &who)
+}
+
+/// Check that the stake recorded by the pallet agrees with the delegations and the holds.
+fn assert_invariants() {
+	for (candidate_id, candidate) in CandidatePool::<Test>::iter() {
+		let delegated: Balance = DelegationInfos::<Test>::iter()
+			.filter(|(_, delegated_to, _)| *delegated_to == candidate_id)
+			.map(|(_, _, delegation)| delegation.amount)
+			.sum();
+		assert_eq!(candidate.sum_delegation, delegated, "sum_delegation of {candidate_id}");
+
+		let indexed: Balance = CandidateDelegators::<Test>::get(candidate_id)
+			.into_inner()
+			.iter()
+			.map(|(_, amount)| *amount)
+			.sum();
+		assert_eq!(indexed, delegated, "delegators index of {candidate_id}");
+	}
+
+	for (delegator, candidate_id, _) in DelegationInfos::<Test>::iter() {
+		assert!(
+			CandidatePool::<Test>::contains_key(candidate_id),
+			"delegation of {delegator} to the unknown candidate {candidate_id}"
+		);
+	}
+
+	for who in ACCOUNTS {
+		let delegate_count = DelegationInfos::<Test>::iter_prefix(who).count() as u32;
+		assert_eq!(DelegateCountMap::<Test>::get(who), delegate_count, "delegate count of {who}");
+
+		let bond = CandidatePool::<Test>::get(who).map(|candidate| candidate.bond).unwrap_or(0);
+		let bond_less = CandidateBondLessRequests::<Test>::get(who)
+			.map(|request| request.amount)
+			.unwrap_or(0);
+		assert_eq!(held(HoldReason::CandidateBondReserved, who), bond + bond_less, "bond of {who}");
+
+		let delegated: Balance = DelegationInfos::<Test>::iter_prefix(who)
+			.map(|(_, delegation)| delegation.amount)
+			.sum();
+		let unbonding: Balance =
+			UnbondingDelegations::<Test>::get(who).iter().map(|request| request.amount).sum();
+		assert_eq!(
+			held(HoldReason::DelegateAmountReserved, who),
+			delegated + unbonding,
+			"delegations of {who}"
+		);
+
+		let deposit =
+			CandidateProfiles::<Test>::get(who).map(|(_, deposit)| deposit).unwrap_or(0);
+		assert_eq!(held(HoldReason::ProfileDeposit, who), deposit, "profile deposit of {who}");
+	}
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(64))]
+
+	#[test]
+	fn staking_accounting_holds(actions in proptest::collection::vec(action(), 1..60)) {
+		ExtBuilder::default()
+			.genesis_candidates(vec![(1, 1_000), (2, 2_000)])
+			.build_and_execute(|| {
+				assert_invariants();
+				for action in actions {
+					apply(action);
+					assert_invariants();
+				}
+			});
+	}
+}
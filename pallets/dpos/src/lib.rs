@@ -18,28 +18,51 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(test)]
+mod invariant_tests;
+
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+mod election;
+pub mod fees;
+pub mod migrations;
 pub mod models;
+mod queries;
+pub mod reward_curve;
+mod slashing;
+#[cfg(any(feature = "try-runtime", test))]
+mod try_state;
+pub mod weights;
+
+pub use fees::*;
+pub use reward_curve::*;
+pub use weights::*;
 
 // https://paritytech.github.io/polkadot-sdk/master/polkadot_sdk_docs/polkadot_sdk/frame_runtime/index.html
 // https://paritytech.github.io/polkadot-sdk/master/polkadot_sdk_docs/guides/your_first_pallet/index.html
 // https://paritytech.github.io/polkadot-sdk/master/frame_support/attr.pallet.html#dev-mode-palletdev_mode
+// The pallet stays in dev mode until `weights.rs` holds benchmarked weights.
 #[frame_support::pallet(dev_mode)]
+// The try-runtime expansion of `#[pallet::hooks]` maps errors only to log them.
+#[allow(clippy::manual_inspect)]
 pub mod pallet {
-	use crate::models::*;
+	use crate::{
+		models::*,
+		reward_curve::{RewardCurve, RewardMode},
+		weights::WeightInfo,
+	};
 	use frame_support::{
 		dispatch::DispatchResult,
 		pallet_prelude::{*, ValueQuery},
 		traits::{
-			fungible::{self, Mutate, MutateHold},
-			tokens::Precision,
+			fungible::{self, Inspect, Mutate, MutateHold},
+			tokens::{Fortitude, Precision, Preservation},
 			FindAuthor,
 		},
-		sp_runtime::traits::{CheckedAdd, CheckedSub, Zero},
-		sp_runtime::{traits::One, BoundedVec, Percent, Saturating},
-		Twox64Concat,
+		sp_runtime::traits::{AccountIdConversion, CheckedAdd, CheckedSub, Zero},
+		sp_runtime::{traits::One, BoundedVec, Perbill, Percent, Saturating},
+		PalletId, Twox64Concat,
 	};
 	use sp_std::prelude::*;
 	use frame_system::pallet_prelude::{OriginFor, *};
@@ -52,12 +75,27 @@ pub mod pallet {
 	pub trait ReportNewValidatorSet<AccountId> {
 		fn report_new_validator_set(_new_set: Vec<AccountId>) {}
 	}
+
+	/// Report an offence committed by a validator of the active set. The pallet implements this
+	/// trait so that the runtime can forward offences from other pallets to it.
+	pub trait ReportOffence<AccountId> {
+		fn report_offence(offender: AccountId, kind: OffenceKind) -> DispatchResult;
+	}
 	
 	pub type BalanceOf<T> = <<T as Config>::NativeBalance as fungible::Inspect<
 		<T as frame_system::Config>::AccountId,
 	>>::Balance;
 
+	/// A validator along with its own stake and the total stake backing it.
+	pub type ValidatorStake<T> =
+		(<T as frame_system::Config>::AccountId, BalanceOf<T>, BalanceOf<T>);
+
+	/// The in-code storage version. A change of the stored layout must bump it and come with a
+	/// migration in the `migrations` module.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -71,6 +109,7 @@ pub mod pallet {
 		/// Type to access the Balances Pallet.
 		type NativeBalance: fungible::Inspect<Self::AccountId>
 		+ fungible::Mutate<Self::AccountId>
+		+ fungible::Balanced<Self::AccountId>
 		+ fungible::hold::Inspect<Self::AccountId>
 		+ fungible::hold::Mutate<Self::AccountId>
 		+ fungible::hold::Mutate<Self::AccountId, Reason = Self::RuntimeHoldReason>
@@ -109,6 +148,54 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxDelegateCount: Get<u32>;
 
+		/// The number of epochs that stake leaving the pool has to wait before its hold is
+		/// released. Must be greater than `SlashDeferEpochs`, so that the deferred slashes still
+		/// reach the leaving stake.
+		#[pallet::constant]
+		type UnbondingEpochs: Get<u32>;
+
+		/// The maximum commission a candidate can take from its block rewards.
+		#[pallet::constant]
+		type MaxCommission: Get<Perbill>;
+
+		/// The minimum number of epochs between two commission changes of a candidate.
+		#[pallet::constant]
+		type CommissionUpdateEpochs: Get<u32>;
+
+		/// The curve deciding the reward minted for each authored block. See the `reward_curve`
+		/// module for the available implementations.
+		type RewardCurve: RewardCurve<Self>;
+
+		/// Whether rewards are minted on each block or split from a fixed budget at the end of
+		/// each epoch.
+		#[pallet::constant]
+		type RewardMode: Get<RewardMode>;
+
+		/// The total reward split between the validators at the end of each epoch when
+		/// `RewardMode` is `EpochBudget`.
+		#[pallet::constant]
+		type EpochRewardBudget: Get<BalanceOf<Self>>;
+
+		/// The share of every block reward and of the collected fees sent to `TreasuryAccount`.
+		#[pallet::constant]
+		type TreasuryCut: Get<Perbill>;
+
+		/// The account receiving the `TreasuryCut` of the rewards.
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		/// The identifier of the pallet. The account derived from it holds the transaction fees
+		/// owed to the block authors until they are claimed.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The maximum length in bytes of each field of a candidate profile.
+		#[pallet::constant]
+		type MaxProfileFieldLength: Get<u32>;
+
+		/// The amount held from a candidate for each byte of its profile.
+		#[pallet::constant]
+		type ProfileDepositPerByte: Get<BalanceOf<Self>>;
+
 		/// The origin which may forcibly create or destroy an item or otherwise alter privileged
 		/// attributes.
 		type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
@@ -119,15 +206,63 @@ pub mod pallet {
 		/// can use a similar mechanism in your tests.
 		type FindAuthor: FindAuthor<Self::AccountId>;
 
+		/// Weight information for the extrinsics of this pallet.
+		type WeightInfo: WeightInfo;
+
 		/// Report the new validators to the runtime. This is done through a custom trait defined in
 		/// this pallet.
 		type ReportNewValidatorSet: ReportNewValidatorSet<Self::AccountId>;
+
+		/// The fraction of the snapshot stake slashed when a validator equivocates.
+		#[pallet::constant]
+		type EquivocationSlashFraction: Get<Perbill>;
+
+		/// The fraction of the snapshot stake slashed when a validator is unresponsive.
+		#[pallet::constant]
+		type UnresponsivenessSlashFraction: Get<Perbill>;
+
+		/// The account receiving the slashed funds. If `None`, the slashed funds are burnt.
+		type SlashDestination: Get<Option<Self::AccountId>>;
+
+		/// The number of epochs a slash waits in `UnappliedSlashes` before it is applied. If zero,
+		/// slashes are applied as soon as the offence is reported.
+		#[pallet::constant]
+		type SlashDeferEpochs: Get<u32>;
+
+		/// The maximum number of slashes that can be deferred in a single epoch.
+		#[pallet::constant]
+		type MaxUnappliedSlashes: Get<u32>;
+
+		/// The maximum number of validators and delegators slashed in a block when the deferred
+		/// slashes are applied. The delegators of a slash are always slashed within the same block.
+		#[pallet::constant]
+		type SlashPageSize: Get<u32>;
+
+		/// The maximum share of `BlockWeights::max_block` that the epoch transition may take with
+		/// `MaxCandidates` candidates of `MaxCandidateDelegators` delegators each. Checked by the
+		/// `integrity_test` of the pallet.
+		#[pallet::constant]
+		type EpochTransitionBlockShare: Get<Perbill>;
+
+		/// The number of blocks before the epoch boundary at which the election of the next
+		/// validator set starts. If zero, the validator set is elected within the boundary block.
+		#[pallet::constant]
+		type ElectionLookahead: Get<BlockNumberFor<Self>>;
+
+		/// The maximum number of candidates and delegators processed by the election in a block.
+		/// The delegators of a validator are always captured within the same block.
+		#[pallet::constant]
+		type ElectionPageSize: Get<u32>;
+
+		/// The maximum number of delegators in a page of `EpochExposurePages`.
+		#[pallet::constant]
+		type MaxExposurePageSize: Get<u32>;
 	}
 
-	/// The pallet's storage items.
-	/// https://paritytech.github.io/polkadot-sdk/master/polkadot_sdk_docs/guides/your_first_pallet/index.html#storage
-	/// https://paritytech.github.io/polkadot-sdk/master/frame_support/pallet_macros/attr.storage.html
-	
+	// The pallet's storage items.
+	// https://paritytech.github.io/polkadot-sdk/master/polkadot_sdk_docs/guides/your_first_pallet/index.html#storage
+	// https://paritytech.github.io/polkadot-sdk/master/frame_support/pallet_macros/attr.storage.html
+
 	/// The candidate pool stores the candidates along with their bond and total delegated amount.
 	#[pallet::storage]
 	pub type CandidatePool<T: Config> = CountedStorageMap<_, Twox64Concat, T::AccountId, Candidate<T>, OptionQuery>;
@@ -137,9 +272,14 @@ pub mod pallet {
 	/// The delegations store the amount of tokens delegated by a delegator to a candidate.
 	#[pallet::storage]
 	pub type DelegationInfos<T: Config> = StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, T::AccountId, Delegation<T>, OptionQuery>;
-	/// The candidate delegators store the delegators of a candidate.
+	/// The candidate delegators store the delegators of a candidate along with their delegated
+	/// amount, ordered from the highest to the lowest delegation.
 	#[pallet::storage]
-	pub type CandidateDelegators<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, BoundedVec<T::AccountId, <T as Config>::MaxCandidateDelegators>, ValueQuery>;
+	pub type CandidateDelegators<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, DelegatorIndex<T>, ValueQuery>;
+	/// The candidate profiles store the public information of a candidate along with the deposit
+	/// held for it.
+	#[pallet::storage]
+	pub type CandidateProfiles<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, (CandidateProfile<T>, BalanceOf<T>), OptionQuery>;
 	/// The current epoch index.
 	#[pallet::storage]
 	pub type EpochIndex<T: Config> = StorageValue<_, u32, ValueQuery>;
@@ -149,16 +289,65 @@ pub mod pallet {
 	/// The active validator set for the current epoch.
 	#[pallet::storage]
 	#[pallet::getter(fn current_validators)]
-	pub type CurrentValidators<T: Config> = StorageValue<_, BoundedVec<(T::AccountId, BalanceOf<T>, BalanceOf<T>), <T as Config>::MaxValidators>, ValueQuery>;
+	pub type CurrentValidators<T: Config> = StorageValue<_, BoundedVec<ValidatorStake<T>, <T as Config>::MaxValidators>, ValueQuery>;
+
+	/// The stake backing each validator of an epoch, captured when the validator set is elected.
+	/// Only the running epoch is kept, along with the next one while it is being elected.
+	#[pallet::storage]
+	pub type EpochExposures<T: Config> = StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, T::AccountId, ExposureOverview<T>, OptionQuery>;
+
+	/// The delegators backing each validator of an epoch, split in pages of at most
+	/// `MaxExposurePageSize` delegators ordered from the highest to the lowest delegation.
+	#[pallet::storage]
+	pub type EpochExposurePages<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, u32>,
+			NMapKey<Twox64Concat, T::AccountId>,
+			NMapKey<Twox64Concat, u32>,
+		),
+		ExposurePage<T>,
+		ValueQuery,
+	>;
+
+	/// The progress of the multi-block election of the next validator set.
+	#[pallet::storage]
+	pub type ElectionStatus<T: Config> = StorageValue<_, ElectionPhase<T::AccountId>, ValueQuery>;
+
+	/// The candidates collected by the running election. Once the candidate pool is exhausted,
+	/// they are ranked and truncated to the next validator set.
+	#[pallet::storage]
+	pub type ElectionCandidates<T: Config> = StorageValue<_, BoundedVec<(T::AccountId, BalanceOf<T>, BalanceOf<T>), <T as Config>::MaxCandidates>, ValueQuery>;
+
+	/// The undelegations of each delegator that are waiting for their unbonding period. A
+	/// delegator can have at most one pending request per candidate.
+	#[pallet::storage]
+	pub type UnbondingDelegations<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, BoundedVec<UnbondingRequest<T>, <T as Config>::MaxDelegateCount>, ValueQuery>;
+
+	/// The bond decreases of each candidate that are waiting for their unbonding period. A
+	/// candidate can have at most one pending request.
+	#[pallet::storage]
+	pub type CandidateBondLessRequests<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, BondLessRequest<T>, OptionQuery>;
+
+	/// The offences reported in each epoch, used to slash a validator at most once per offence
+	/// kind and epoch.
+	#[pallet::storage]
+	pub type OffenceReports<T: Config> = StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, (T::AccountId, OffenceKind), (), OptionQuery>;
 
-	/// Snapshot of the last epoch data, which includes the active validator set along with their
-	/// total bonds and delegations. This storage is unbounded but safe, as it only stores `Vec`
-	/// values within a `BoundedVec`. The total number of delegations is limited by the size
-	/// `MaxValidators * MaxCandidateDelegators`.
+	/// The slashes waiting to be applied, keyed by the epoch index in which the offence was
+	/// reported. They are applied `SlashDeferEpochs` epochs later unless cancelled.
 	#[pallet::storage]
-	#[pallet::unbounded]
-	#[pallet::getter(fn last_epoch_snapshot)]
-	pub type LastEpochSnapshot<T: Config> = StorageValue<_, Epoch<T>, OptionQuery>;
+	pub type UnappliedSlashes<T: Config> = StorageMap<_, Twox64Concat, u32, BoundedVec<UnappliedSlash<T>, <T as Config>::MaxUnappliedSlashes>, ValueQuery>;
+
+	/// The oldest epoch whose deferred slashes may not be applied yet. Set when the first slash is
+	/// deferred, the due slashes are then applied in pages from this epoch on.
+	#[pallet::storage]
+	pub type NextSlashEpoch<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+	/// The number of blocks authored by each validator during the current epoch. Only used when
+	/// `RewardMode` is `EpochBudget`.
+	#[pallet::storage]
+	pub type AuthoredBlocks<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
 
 	/// Stores the total claimable rewards for each account, which can be a validator or a
 	/// delegator. The reward points are updated with each block produced.
@@ -168,7 +357,13 @@ pub mod pallet {
 	// genesis config
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
+		/// The candidates registered at genesis with their bond.
 		pub genesis_candidates: CandidateSet<T>,
+		/// The delegations made at genesis, as the delegator, the candidate and the amount. The
+		/// candidates must be registered in `genesis_candidates`.
+		pub genesis_delegations: DelegationSet<T>,
+		/// The index of the first epoch of the chain.
+		pub initial_epoch_index: u32,
 	}
 
 	#[pallet::genesis_build]
@@ -179,15 +374,66 @@ pub mod pallet {
 				"Need at least one validator for the network to function"
 			);
 
+			assert!(
+				self.genesis_candidates.len() as u32 <= T::MaxCandidates::get(),
+				"Genesis candidates exceed `MaxCandidates`"
+			);
+			EpochIndex::<T>::put(self.initial_epoch_index);
+
 			// Populates the provided genesis candidates with bond in storage.
 			// Ensures that there are no duplicate candidates in the `genesis_candidates`.
 			let mut visited: BTreeSet<T::AccountId> = BTreeSet::default();
-			for (candidateId, bond) in self.genesis_candidates.iter() {
-				assert!(visited.insert(candidateId.clone()), "Candidate registration duplicates");
+			for (candidate_id, bond) in self.genesis_candidates.iter() {
+				assert!(visited.insert(candidate_id.clone()), "Candidate registration duplicates");
+				assert!(
+					*bond >= T::MinCandidateBond::get(),
+					"Genesis candidate bond is below `MinCandidateBond`"
+				);
+
+				let reason: T::RuntimeHoldReason = HoldReason::CandidateBondReserved.into();
+				T::NativeBalance::hold(&reason, candidate_id, *bond).unwrap_or_else(|e| {
+					panic!("Failed to hold the bond of genesis candidate {candidate_id:?}: {e:?}")
+				});
+				let candidate = Candidate::new(*bond, Perbill::zero(), self.initial_epoch_index);
+				CandidatePool::<T>::insert(candidate_id, candidate);
+			}
+
+			// Populates the provided genesis delegations. Unlike `delegate`, a full candidate
+			// doesn't kick its lowest delegator, the genesis is rejected instead.
+			for (delegator, candidate_id, amount) in self.genesis_delegations.iter() {
+				assert!(
+					CandidatePool::<T>::contains_key(candidate_id),
+					"Genesis delegation to a candidate that is not registered"
+				);
+				assert!(
+					!DelegationInfos::<T>::contains_key(delegator, candidate_id),
+					"Genesis delegation duplicates"
+				);
+				assert!(
+					*amount >= T::MinDelegateAmount::get(),
+					"Genesis delegation is below `MinDelegateAmount`"
+				);
+				let delegate_count = DelegateCountMap::<T>::get(delegator).saturating_add(1);
+				assert!(
+					delegate_count <= T::MaxDelegateCount::get(),
+					"Genesis delegator delegates to more than `MaxDelegateCount` candidates"
+				);
+				let inserted = CandidateDelegators::<T>::mutate(candidate_id, |delegators| {
+					delegators.insert(delegator.clone(), *amount)
+				});
+				assert!(
+					inserted,
+					"Genesis candidate has more than `MaxCandidateDelegators` delegators"
+				);
 
-				let _ = T::NativeBalance::hold(&HoldReason::CandidateBondReserved.into(), &candidateId, *bond);
-				let candidate = Candidate::new(*bond);
-				CandidatePool::<T>::insert(&candidateId, candidate);
+				let reason: T::RuntimeHoldReason = HoldReason::DelegateAmountReserved.into();
+				T::NativeBalance::hold(&reason, delegator, *amount).unwrap_or_else(|e| {
+					panic!("Failed to hold the genesis delegation of {delegator:?}: {e:?}")
+				});
+				DelegationInfos::<T>::insert(delegator, candidate_id, Delegation::new(*amount));
+				DelegateCountMap::<T>::insert(delegator, delegate_count);
+				Pallet::<T>::increase_candidate_delegations(candidate_id, amount)
+					.expect("The candidate is registered above; qed");
 			}
 
 			// Update the validator set using the data stored in the candidate pool
@@ -196,10 +442,8 @@ pub mod pallet {
 				BoundedVec::try_from(validator_set.clone())
 					.expect("Exceed limit number of the validators in the active set"),
 			);
-			// Capture the snapshot of the last epoch
-			LastEpochSnapshot::<T>::set(Some(Pallet::<T>::capture_epoch_snapshot(
-				&validator_set,
-			)));
+			// Capture the stake backing the validators of the first epoch
+			Pallet::<T>::capture_exposures(EpochIndex::<T>::get(), &validator_set);
 
 			// Report the new validator set to the runtime
 			let new_set = CurrentValidators::<T>::get()
@@ -213,7 +457,11 @@ pub mod pallet {
 
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
-			GenesisConfig { genesis_candidates: vec![] }
+			GenesisConfig {
+				genesis_candidates: vec![],
+				genesis_delegations: vec![],
+				initial_epoch_index: 0,
+			}
 		}
 	}
 
@@ -222,20 +470,68 @@ pub mod pallet {
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		/// We execute the rewards calculation for last epoch block and the validator set selection logic at the start of
 		/// each block.
+		///
+		/// The reward of the block author, or the count of its authored blocks, is metered in every
+		/// block, as are the epoch boundary and the pages of the election and of the deferred
+		/// slashes. The weight of the epoch boundary covers the payout of an epoch budget and the
+		/// removal of the exposures and offence reports of the ending epoch. It grows with the
+		/// number of candidates and the number of delegators of each validator, unless the
+		/// validator set was elected over several blocks beforehand. See the `election` and
+		/// `slashing` modules.
 		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
-			Self::execute_rewards();
+			let reward_mode = T::RewardMode::get();
+			let reward_weight = match reward_mode {
+				RewardMode::PerBlock => Self::execute_rewards(),
+				RewardMode::EpochBudget => Self::note_authored_block(),
+			};
+			let mut weight = Weight::zero();
 			let epoch_indx = n % T::EpochDuration::get();
 			if epoch_indx == BlockNumberFor::<T>::zero() {
-				let validator_set = Self::select_validator_set();
+				let candidate_count = CandidatePool::<T>::count();
+				// The payout and the cleanup of the ending epoch grow with its validators
+				let ending_validators =
+					CurrentValidators::<T>::decode_len().unwrap_or_default() as u32;
+				// The epoch budget is split using the snapshot of the ending epoch
+				if reward_mode == RewardMode::EpochBudget {
+					Self::execute_epoch_rewards();
+				}
+
+				// A complete election already captured the exposures of the next epoch,
+				// otherwise the next validator set is elected within this block
+				let next_epoch = EpochIndex::<T>::get().saturating_add(1);
+				let validator_set = match Self::take_elected() {
+					Some(validator_set) => {
+						// Every validator is assumed to have a full delegators index
+						weight = T::WeightInfo::on_initialize_epoch_swap(
+							(validator_set.len() as u32).max(ending_validators),
+							T::MaxCandidateDelegators::get(),
+						);
+						validator_set
+					},
+					None => {
+						if !T::ElectionLookahead::get().is_zero() {
+							Self::deposit_event(Event::ElectionFallback {
+								epoch_index: EpochIndex::<T>::get(),
+							});
+						}
+						// Every validator is assumed to have a full delegators index, as reading
+						// their actual size would cost as much as capturing them
+						weight = T::WeightInfo::on_initialize_epoch_transition(
+							candidate_count.max(ending_validators),
+							T::MaxCandidateDelegators::get(),
+						);
+						// Drop the exposures of an election that did not complete
+						Self::clear_exposures(next_epoch);
+						let validator_set = Self::select_validator_set();
+						Self::capture_exposures(next_epoch, &validator_set);
+						validator_set
+					},
+				};
 
 				CurrentValidators::<T>::put(
 					BoundedVec::try_from(validator_set.to_vec())
 						.expect("Exceed limit number of the validators in the active set"),
 				);
-				// In new epoch, we want to set the CurrentEpochSnapshot to the current dataset
-				LastEpochSnapshot::<T>::set(Some(Pallet::<T>::capture_epoch_snapshot(
-					&validator_set,
-				)));
 
 				let new_set = CurrentValidators::<T>::get()
 					.iter()
@@ -245,11 +541,76 @@ pub mod pallet {
 				Pallet::<T>::report_new_validators(new_set);
 				Self::move_to_next_epoch(validator_set);
 			}
-			// We return a default weight because we do not expect you to do weights for your
-			// project... Except for extra credit...
-			return Weight::default();
+
+			if Self::election_starts_at(n) {
+				Self::start_election();
+			}
+			if ElectionStatus::<T>::get().is_running() {
+				let processed = Self::process_election_page(T::ElectionPageSize::get());
+				weight = weight
+					.saturating_add(T::WeightInfo::on_initialize_election_page(processed));
+			}
+			// The due slashes are applied in pages, from the block of the epoch boundary on
+			let slashed = Self::apply_deferred_slashes(T::SlashPageSize::get());
+			weight
+				.saturating_add(T::WeightInfo::on_initialize_slash_page(slashed))
+				.saturating_add(reward_weight)
+		}
+
+		fn integrity_test() {
+			// The benchmarks of the epoch boundary include the payout and the cleanup of a full
+			// ending epoch, the deferred slashes are metered by their own pages and the reward of
+			// the block author is paid in every block
+			let max_weight = T::EpochTransitionBlockShare::get() *
+				<T as frame_system::Config>::BlockWeights::get().max_block;
+			assert!(T::SlashPageSize::get() > 0, "A slash page must process items");
+			assert!(
+				T::UnbondingEpochs::get() > T::SlashDeferEpochs::get(),
+				"The leaving stake must stay on hold until the deferred slashes are applied"
+			);
+			let slash_page = T::WeightInfo::on_initialize_slash_page(
+				T::SlashPageSize::get().saturating_add(T::MaxCandidateDelegators::get()),
+			);
+			let block_reward = match T::RewardMode::get() {
+				RewardMode::PerBlock =>
+					T::WeightInfo::on_initialize_block_reward(T::MaxCandidateDelegators::get()),
+				RewardMode::EpochBudget => T::DbWeight::get().reads_writes(2, 1),
+			};
+			let worst_case = if T::ElectionLookahead::get().is_zero() {
+				T::WeightInfo::on_initialize_epoch_transition(
+					T::MaxCandidates::get(),
+					T::MaxCandidateDelegators::get(),
+				)
+			} else {
+				assert!(
+					T::ElectionLookahead::get() < T::EpochDuration::get(),
+					"The election must start after the previous epoch boundary"
+				);
+				assert!(T::ElectionPageSize::get() > 0, "An election page must process items");
+				T::WeightInfo::on_initialize_epoch_swap(
+					T::MaxValidators::get(),
+					T::MaxCandidateDelegators::get(),
+				)
+				.max(T::WeightInfo::on_initialize_election_page(
+					T::ElectionPageSize::get().saturating_add(T::MaxCandidateDelegators::get()),
+				))
+			}
+			.saturating_add(slash_page)
+			.saturating_add(block_reward);
+			assert!(
+				worst_case.all_lte(max_weight),
+				"The epoch transition can take {:?}, above `EpochTransitionBlockShare` of the block \
+				 weight ({:?}). Lower `MaxCandidates`, `MaxCandidateDelegators` or `SlashPageSize`, \
+				 or elect the validator set over several blocks with `ElectionLookahead`.",
+				worst_case,
+				max_weight,
+			);
 		}
 
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
 	}
 	/// Pallets use events to inform users when important changes are made.
 	/// https://paritytech.github.io/polkadot-sdk/master/polkadot_sdk_docs/guides/your_first_pallet/index.html#event-and-error
@@ -259,9 +620,21 @@ pub mod pallet {
 		/// We usually use passive tense for events.
 		SomethingStored { something: u32, who: T::AccountId },
 		/// Event emitted when there is a new candidate registered
-		CandidateRegistered { candidate_id: T::AccountId, initial_bond: BalanceOf<T> },
+		CandidateRegistered {
+			candidate_id: T::AccountId,
+			initial_bond: BalanceOf<T>,
+			commission: Perbill,
+		},
+		/// Event emitted when a candidate updates its commission
+		CommissionUpdated { candidate_id: T::AccountId, commission: Perbill },
+		/// Event emitted when a candidate sets its profile
+		CandidateProfileSet { candidate_id: T::AccountId, deposit: BalanceOf<T> },
+		/// Event emitted when the profile of a candidate is removed
+		CandidateProfileCleared { candidate_id: T::AccountId, deposit: BalanceOf<T> },
 		/// Event emitted when a candidate is removed from the candidate pool
 		CandidateRegistrationRemoved { candidate_id: T::AccountId },
+		/// Event emitted when a candidate requests to leave the candidate pool
+		CandidateLeavingScheduled { candidate_id: T::AccountId, exit_epoch: u32 },
 		/// Event emitted when a candidate is delegated by a delegator
 		CandidateDelegated {
 			candidate_id: T::AccountId,
@@ -276,6 +649,72 @@ pub mod pallet {
 			amount: BalanceOf<T>,
 			left_delegated_amount: BalanceOf<T>,
 		},
+		/// Event emitted when a delegator schedules an undelegation from a candidate
+		UndelegateScheduled {
+			candidate_id: T::AccountId,
+			delegator: T::AccountId,
+			amount: BalanceOf<T>,
+			unlock_epoch: u32,
+		},
+		/// Event emitted when an unbonded delegation is released to the delegator
+		UndelegateExecuted { candidate_id: T::AccountId, delegator: T::AccountId, amount: BalanceOf<T> },
+		/// Event emitted when a pending undelegation is added back to the delegation
+		UndelegateCancelled {
+			candidate_id: T::AccountId,
+			delegator: T::AccountId,
+			amount: BalanceOf<T>,
+			total_delegated_amount: BalanceOf<T>,
+		},
+		/// Event emitted when a candidate increases its bond
+		CandidateBondIncreased { candidate_id: T::AccountId, amount: BalanceOf<T>, new_bond: BalanceOf<T> },
+		/// Event emitted when a candidate schedules a decrease of its bond
+		CandidateBondLessScheduled {
+			candidate_id: T::AccountId,
+			amount: BalanceOf<T>,
+			new_bond: BalanceOf<T>,
+			unlock_epoch: u32,
+		},
+		/// Event emitted when a decreased bond is released to the candidate
+		CandidateBondLessExecuted { candidate_id: T::AccountId, amount: BalanceOf<T> },
+		/// Event emitted when a pending bond decrease is added back to the bond
+		CandidateBondLessCancelled { candidate_id: T::AccountId, amount: BalanceOf<T>, new_bond: BalanceOf<T> },
+		/// Event emitted when a validator and its delegators are slashed for an offence
+		Slashed {
+			validator: T::AccountId,
+			kind: OffenceKind,
+			slash_fraction: Perbill,
+			epoch_index: u32,
+			validator_amount: BalanceOf<T>,
+			delegators_amount: BalanceOf<T>,
+		},
+		/// Event emitted when a delegator is slashed for an offence of its validator
+		DelegatorSlashed { delegator: T::AccountId, validator: T::AccountId, amount: BalanceOf<T> },
+		/// Event emitted when the slash of a reported offence is deferred
+		SlashDeferred {
+			validator: T::AccountId,
+			kind: OffenceKind,
+			epoch_index: u32,
+			apply_at_epoch: u32,
+		},
+		/// Event emitted when a deferred slash is cancelled before being applied
+		DeferredSlashCancelled { validator: T::AccountId, kind: OffenceKind, epoch_index: u32 },
+		/// Event emitted when the lowest delegator of a full candidate is replaced by a higher
+		/// delegation
+		DelegatorKicked {
+			candidate_id: T::AccountId,
+			delegator: T::AccountId,
+			amount: BalanceOf<T>,
+			unlock_epoch: u32,
+		},
+		/// Event emitted when a delegator updates the auto-compounding of a delegation
+		AutoCompoundSet { candidate_id: T::AccountId, delegator: T::AccountId, value: Percent },
+		/// Event emitted when a share of a delegator reward is delegated again automatically
+		RewardCompounded {
+			candidate_id: T::AccountId,
+			delegator: T::AccountId,
+			amount: BalanceOf<T>,
+			total_delegated_amount: BalanceOf<T>,
+		},
 		/// Event emitted when the next epoch is moved
 		NextEpochMoved {
 			last_epoch: u32,
@@ -284,6 +723,11 @@ pub mod pallet {
 			total_candidates: u64,
 			total_validators: u64,
 		},
+		/// Event emitted when the election of the next validator set did not complete before the
+		/// epoch boundary and the validator set was elected within the boundary block
+		ElectionFallback { epoch_index: u32 },
+		/// Event emitted when the epoch reward budget is split between the validators
+		EpochRewardsDistributed { epoch_index: u32, total_blocks: u32, total_reward: BalanceOf<T> },
 		/// Event emitted when a reward is claimed
 		RewardClaimed { claimer: T::AccountId, total_reward: BalanceOf<T> },
 	}
@@ -314,6 +758,44 @@ pub mod pallet {
 		NoClaimableRewardFound,
 		/// Thrown when the candidate has too many delegations exceeding the allowed limit
 		InvalidMinimumDelegateAmount,
+		/// Thrown when the candidate already requested to leave the candidate pool
+		CandidateAlreadyLeaving,
+		/// Thrown when the candidate did not request to leave the candidate pool
+		CandidateNotLeaving,
+		/// Thrown when the unbonding period of the leaving candidate has not passed yet
+		CandidateCannotLeaveYet,
+		/// Thrown when the candidate is leaving the pool and does not accept delegations
+		CandidateIsLeaving,
+		/// Thrown when the delegator already has a pending undelegation from the candidate
+		PendingUndelegateRequestExists,
+		/// Thrown when the delegator has no pending undelegation from the candidate
+		UndelegateRequestDoesNotExist,
+		/// Thrown when the delegator has too many pending undelegations
+		TooManyUnbondingRequests,
+		/// Thrown when none of the pending undelegations has passed its unbonding period
+		NoUnbondedDelegationFound,
+		/// Thrown when the candidate already has a pending bond decrease
+		PendingBondLessRequestExists,
+		/// Thrown when the candidate has no pending bond decrease
+		BondLessRequestDoesNotExist,
+		/// Thrown when the unbonding period of the bond decrease has not passed yet
+		BondLessRequestNotUnlocked,
+		/// Thrown when the reported offender is not a validator of the epoch snapshot
+		OffenderNotValidator,
+		/// Thrown when the offence was already reported for the validator in this epoch
+		DuplicateOffenceReport,
+		/// Thrown when there are too many deferred slashes in the epoch
+		TooManyUnappliedSlashes,
+		/// Thrown when no slash index is provided for cancellation
+		EmptySlashIndices,
+		/// Thrown when the slash indices are not sorted, unique and within the deferred slashes
+		InvalidSlashIndex,
+		/// Thrown when the commission is above `MaxCommission`
+		CommissionTooHigh,
+		/// Thrown when the commission was updated less than `CommissionUpdateEpochs` epochs ago
+		CommissionUpdateTooSoon,
+		/// Thrown when a profile field is longer than `MaxProfileFieldLength`
+		ProfileFieldTooLong,
 	}
 
 	/// A reason for the pallet dpos placing a hold on funds.
@@ -325,6 +807,9 @@ pub mod pallet {
 		/// Hold the amount delegated to the candidate
 		#[codec(index = 1)]
 		DelegateAmountReserved,
+		/// Hold the deposit of a candidate profile
+		#[codec(index = 2)]
+		ProfileDeposit,
 	}
 
 	/// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -334,6 +819,8 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// An example of directly updating the authorities into [`Config::ReportNewValidatorSet`].
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::force_report_new_validators(new_set.len() as u32))]
 		pub fn force_report_new_validators(
 			origin: OriginFor<T>,
 			new_set: Vec<T::AccountId>,
@@ -355,28 +842,36 @@ pub mod pallet {
 		/// (`MinCandidateBond`).
 		///
 		/// If successful, the caller's account is registered as a candidate with the specified bond
-		/// amount, and a `CandidateRegistered` event is emitted.
+		/// amount and commission, and a `CandidateRegistered` event is emitted.
 		///
 		/// Parameters:
 		/// - `origin`: The origin of the transaction.
 		/// - `bond`: The amount of funds to bond as part of the candidate registration.
+		/// - `commission`: The share of each block reward the candidate takes before splitting it
+		///   with its delegators.
 		///
 		/// Errors:
 		/// - `InvalidZeroAmount`: Raised if `bond` is zero.
 		/// - `BelowMinimumCandidateBond`: Raised if `bond` is less than `MinCandidateBond`.
+		/// - `CommissionTooHigh`: Raised if `commission` is above `MaxCommission`.
 		/// - `CandidateAlreadyExist`: Raised if the caller is already registered as a candidate.
 		///
 		/// Emits:
 		/// - `CandidateRegistered`: When a candidate successfully registers, including the
-		///   candidate's account ID (`candidate_id`) and the initial bond amount (`initial_bond`).
+		///   candidate's account ID (`candidate_id`), the initial bond amount (`initial_bond`) and
+		///   the commission (`commission`).
 		///
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::register_as_candidate(T::MaxCandidates::get()))]
 		pub fn register_as_candidate(
 			origin: OriginFor<T>,
 			initial_bond: BalanceOf<T>,
+			commission: Perbill,
 		) -> DispatchResult {
 			// Ensure the bond amount is greater than zero and at least equal to the minimum required
 			ensure!(initial_bond > Zero::zero(), Error::<T>::InvalidZeroAmount);
 			ensure!(initial_bond >= T::MinCandidateBond::get(), Error::<T>::BelowMinimumCandidateBond);
+			ensure!(commission <= T::MaxCommission::get(), Error::<T>::CommissionTooHigh);
 
 			let who = ensure_signed(origin)?;
 			ensure!(!Self::is_candidate(&who), Error::<T>::CandidateAlreadyExist);
@@ -389,10 +884,14 @@ pub mod pallet {
 			T::NativeBalance::hold(&HoldReason::CandidateBondReserved.into(), &who, initial_bond)?;
 
 			// Register the candidate in the candidate pool
-			let candidate = Candidate::new(initial_bond);
+			let candidate = Candidate::new(initial_bond, commission, EpochIndex::<T>::get());
 			CandidatePool::<T>::insert(&who, candidate);
 			// Emit an event to notify that the candidate has been registered
-			Self::deposit_event(Event::CandidateRegistered { candidate_id: who, initial_bond });
+			Self::deposit_event(Event::CandidateRegistered {
+				candidate_id: who,
+				initial_bond,
+				commission,
+			});
 			Ok(())
 		}
 
@@ -412,8 +911,14 @@ pub mod pallet {
 		///
 		/// Errors:
 		/// - `InvalidZeroAmount`: Raised if `amount` is zero.
+		/// - `CandidateDoesNotExist`: Raised if the candidate is not in the candidate pool.
+		/// - `CandidateIsLeaving`: Raised if the candidate requested to leave the pool.
 		/// - `TooManyCandidateDelegations`: Raised if the delegator exceeds the maximum allowed
 		///   number of candidate delegations.
+		/// - `TooManyDelegatorsInPool`: Raised if the candidate has `MaxCandidateDelegators`
+		///   delegators and `amount` is not higher than the lowest delegation.
+		/// - `TooManyUnbondingRequests`: Raised if the unbonding queue of the delegator to kick is
+		///   full.
 		/// - `BalanceOverflow`: Raised if adding `amount` to an existing delegated amount results
 		///   in overflow.
 		///
@@ -421,6 +926,8 @@ pub mod pallet {
 		/// - Updates the delegated amount for the specified candidate and delegator.
 		/// - Increases the count of candidates delegated to by the delegator if it's the first time
 		///   delegating to this candidate.
+		/// - Kicks the lowest delegator of a full candidate and queues its delegated amount for
+		///   release after `UnbondingEpochs` epochs.
 		/// - Holds `amount` from the delegator's account as delegated amount.
 		///
 		/// Emits:
@@ -428,7 +935,13 @@ pub mod pallet {
 		///   including the candidate's account ID (`candidate_id`), delegator's account ID
 		///   (`delegated_by`), the delegated amount (`amount`), and the total delegated amount to
 		///   the candidate after the delegation (`total_delegated_amount`).
+		/// - `DelegatorKicked`: When the lowest delegator of a full candidate is replaced,
+		///   including the candidate's account ID (`candidate_id`), the kicked delegator's account
+		///   ID (`delegator`), the unbonding amount (`amount`) and the epoch index from which it
+		///   can be released (`unlock_epoch`).
 		///
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::delegate(T::MaxCandidateDelegators::get()))]
 		pub fn delegate(
 			origin: OriginFor<T>,
 			candidate: T::AccountId,
@@ -436,37 +949,8 @@ pub mod pallet {
 		) -> DispatchResult {
 			ensure!(amount > Zero::zero(), Error::<T>::InvalidZeroAmount);
 			let delegator = ensure_signed(origin)?;
-			match DelegationInfos::<T>::try_get(&delegator, &candidate) {
-				// If the delegator has previously delegated to the candidate, update the delegated amount
-				Ok(mut delegation_info) => {
-					// Check if the new delegated amount will overflow
-					let new_delegated_amount =
-						delegation_info.amount.checked_add(&amount).expect("Overflow");
-					Self::check_delegated_amount(new_delegated_amount)?;
-					// Update the delegated amount
-					delegation_info.set_amount(new_delegated_amount);
-					DelegationInfos::<T>::set(&delegator, &candidate, Some(delegation_info));
-				},
-				Err(_) => {
-					// If it's the first time delegation, initialize a new delegation record
-					// Check if the new delegated amount will overflow
-					Self::check_delegated_amount(amount)?;
-					let delegate_count = DelegateCountMap::<T>::get(&delegator);
-					let new_delegate_count = delegate_count.saturating_add(1);
-					ensure!(
-						new_delegate_count <= T::MaxDelegateCount::get(),
-						Error::<T>::TooManyCandidateDelegations
-					);
-					// Update the delegator's delegate count
-					DelegateCountMap::<T>::set(&delegator, new_delegate_count);
-					// Update the candidate's delegator list
-					Self::add_candidate_delegator(&candidate, &delegator)?;
-					// Initialize a new delegation record
-					let new_delegation_info = Delegation::new(amount);
-					// Set the new delegation record
-					DelegationInfos::<T>::insert(&delegator, &candidate, new_delegation_info);
-				},
-			};
+			ensure!(Self::get_candidate(&candidate)?.is_active(), Error::<T>::CandidateIsLeaving);
+			Self::add_delegation(&delegator, &candidate, amount)?;
 			// Hold the delegated amount from the delegator's account
 			T::NativeBalance::hold(&HoldReason::DelegateAmountReserved.into(), &delegator, amount)?;
 
@@ -502,42 +986,12 @@ pub mod pallet {
 		///  candidate pool, including the candidate's account ID (`candidate_id`).
 		/// - `RewardClaimed`: When a candidate claims the reward, including the claimer's account ID
 		/// (`claimer`) and the total reward claimed (`total_reward`).
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::unregister_as_candidate(T::MaxCandidateDelegators::get()))]
 		pub fn unregister_as_candidate(origin: OriginFor<T>, candidate: T::AccountId) -> DispatchResult {
 			T::ForceOrigin::ensure_origin(origin)?;
 			ensure!(Self::is_candidate(&candidate), Error::<T>::CandidateDoesNotExist);
-			let candidate_delegators = CandidateDelegators::<T>::get(&candidate);
-
-			// Processing all the delegators of the candidate
-			for delegator in candidate_delegators.into_inner() {
-				let delegation_info = DelegationInfos::<T>::try_get(&delegator, &candidate)
-					.map_err(|_| Error::<T>::DelegationDoesNotExist)?;
-
-				// Trying to release all the hold amount of the delegators
-				Self::release_delegated_amount(&delegator, &delegation_info.amount)?;
-
-				// Removing any information related to the delegation between (candidate, delegator)
-				Self::remove_candidate_delegation_data(&delegator, &candidate)?;
-			}
-			CandidateDelegators::<T>::remove(&candidate);
-
-			// Releasing the hold bonds of the candidate
-			let candidate_detail = Self::get_candidate(&candidate)?;
-			Self::release_candidate_bonds(&candidate, candidate_detail.bond)?;
-			// Claiming the rewards of the candidate
-			let rewards = Rewards::<T>::get(&candidate);
-			if rewards > Zero::zero() {
-				// Mint the rewards to the candidate
-				let _ = T::NativeBalance::mint_into(&candidate, rewards);
-				// Remove the rewards from the storage
-				Rewards::<T>::remove(&candidate);
-				Self::deposit_event(Event::RewardClaimed { claimer: candidate.clone(), total_reward: rewards });
-			}
-			// Removing any information related the registration of the candidate in the pool
-			CandidatePool::<T>::remove(&candidate);
-
-			Self::deposit_event(Event::CandidateRegistrationRemoved { candidate_id: candidate.clone() });
-
-			Ok(())
+			Self::remove_candidate(&candidate)
 		}
 
 		/// Undelegates a specified amount of funds from a candidate in the DPoS
@@ -565,6 +1019,8 @@ pub mod pallet {
 		///  including the candidate's account ID (`candidate_id`), the delegator's account ID
 		/// (`delegator`), the undelegated amount (`amount`), and the remaining delegated amount to
 		/// the candidate after the undelegation (`left_delegated_amount`).
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::undelegate(T::MaxCandidateDelegators::get()))]
 		pub fn undelegate(
 			origin: OriginFor<T>,
 			delegator: T::AccountId,
@@ -578,24 +1034,7 @@ pub mod pallet {
 			);
 			ensure!(amount > Zero::zero(), Error::<T>::InvalidZeroAmount);
 
-			let mut delegation_info = Self::get_delegation(&delegator, &candidate)?;
-			let new_delegated_amount = delegation_info
-				.amount
-				.checked_sub(&amount)
-				.ok_or(Error::<T>::InvalidMinimumDelegateAmount)?;
-
-			if new_delegated_amount.is_zero() {
-				// If the delegated amount is removed completely, we want to remove
-				// related information to the delegation betwene (delegator, candidate)
-				Self::remove_candidate_delegation_data(&delegator, &candidate)?;
-			} else {
-				// Remove the delegated amoutn partially but makes sure it is still above
-				// the minimum delegated amount
-				Self::check_delegated_amount(new_delegated_amount)?;
-
-				delegation_info.set_amount(new_delegated_amount);
-				DelegationInfos::<T>::set(&delegator, &candidate, Some(delegation_info));
-			}
+			let new_delegated_amount = Self::reduce_delegation(&delegator, &candidate, amount)?;
 
 			// Releasing the hold amount for the delegation betwene (delegator, candidate)
 			Self::release_delegated_amount(&delegator, &amount)?;
@@ -624,105 +1063,866 @@ pub mod pallet {
 		///   `Error`.
 		///
 		/// Effects:
-		/// - Mints native tokens into the claimer's account equivalent to their accumulated reward
-		///   points.
+		/// - Pays the claimer's accumulated reward points from the fees held by the reward pot
+		///   account, and mints the rest as native tokens.
 		/// - Removes the claimer's accumulated reward points from storage after claiming.
 		/// - Emits a `RewardClaimed` event upon successful claim.
 		/// 
 		/// Emits:
 		/// - `RewardClaimed`: When a claimer successfully claims their reward, including the
 		///  claimer's account ID (`claimer`) and the total reward claimed (`total_reward`).
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::claim_reward())]
 		pub fn claim_reward(origin: OriginFor<T>) -> DispatchResult {
 			let claimer = ensure_signed(origin)?;
 
 			let rewards = Rewards::<T>::try_get(&claimer)
 				.map_err(|_| Error::<T>::NoClaimableRewardFound)?;
 			ensure!(rewards > Zero::zero(), Error::<T>::NoClaimableRewardFound);
-			let _ = T::NativeBalance::mint_into(&claimer, rewards);
+			// The fees of the block authors are already issued, only the block rewards are minted
+			let reward_pot = Self::reward_pot_account();
+			let from_pot = T::NativeBalance::reducible_balance(
+				&reward_pot,
+				Preservation::Expendable,
+				Fortitude::Polite,
+			)
+			.min(rewards);
+			if !from_pot.is_zero() {
+				T::NativeBalance::transfer(
+					&reward_pot,
+					&claimer,
+					from_pot,
+					Preservation::Expendable,
+				)?;
+			}
+			let to_mint = rewards.saturating_sub(from_pot);
+			if !to_mint.is_zero() {
+				T::NativeBalance::mint_into(&claimer, to_mint)?;
+			}
 			Rewards::<T>::remove(&claimer);
 
 			Self::deposit_event(Event::RewardClaimed { claimer, total_reward: rewards });
 			Ok(())
 		}
-	}
-
-	impl<T: Config> Pallet<T> {
-		// A function to get you an account id for the current block author.
-		pub fn find_author() -> Option<T::AccountId> {
-			// If you want to see a realistic example of the `FindAuthor` interface, see
-			// `pallet-authorship`.
-			T::FindAuthor::find_author::<'_, Vec<_>>(Default::default())
-		}
-	}
-
-	/// implementation of some util function the pallet
-	impl<T: Config> Pallet<T> {
-		/// Get the delegation information between a delegator and a candidate.
-		pub fn get_delegation(
-			delegator: &T::AccountId,
-			candidate: &T::AccountId,
-		) -> DispatchResultWithValue<Delegation<T>> {
-			Ok(DelegationInfos::<T>::try_get(&delegator, &candidate)
-				.map_err(|_| Error::<T>::DelegationDoesNotExist)?)
-		}
 
-		/// Get the candidate information from the candidate pool.
-		pub fn get_candidate(
-			candidate: &T::AccountId,
-		) -> DispatchResultWithValue<Candidate<T>> {
-			Ok(CandidatePool::<T>::try_get(&candidate)
-				.map_err(|_| Error::<T>::CandidateDoesNotExist)?)
-		}
+		/// Requests to leave the candidate pool of the DPoS (Delegated Proof of Stake) network.
+		///
+		/// The candidate is excluded from the validator selection right away, but its bond and
+		/// the delegations to it stay on hold for `UnbondingEpochs` epochs. The exit is finalized
+		/// with `execute_leave_candidates`.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the candidate.
+		///
+		/// Errors:
+		/// - `CandidateDoesNotExist`: Raised if the caller is not in the candidate pool.
+		/// - `CandidateAlreadyLeaving`: Raised if the caller already requested to leave.
+		///
+		/// Emits:
+		/// - `CandidateLeavingScheduled`: When the request is recorded, including the candidate's
+		///   account ID (`candidate_id`) and the epoch index from which the exit can be executed
+		///   (`exit_epoch`).
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::leave_candidate_pool())]
+		pub fn leave_candidate_pool(origin: OriginFor<T>) -> DispatchResult {
+			let candidate = ensure_signed(origin)?;
+			let mut candidate_detail = Self::get_candidate(&candidate)?;
+			ensure!(candidate_detail.is_active(), Error::<T>::CandidateAlreadyLeaving);
 
-		/// Check if the candidate is in the candidate pool.
-		pub fn is_candidate(validator: &T::AccountId) -> bool {
-			CandidatePool::<T>::contains_key(&validator)
-		}
+			let exit_epoch = EpochIndex::<T>::get().saturating_add(T::UnbondingEpochs::get());
+			candidate_detail.leave(exit_epoch);
+			CandidatePool::<T>::set(&candidate, Some(candidate_detail));
 
-		/// Check if the delegator has delegated to the candidate.
-		fn check_delegated_amount(amount: BalanceOf<T>) -> DispatchResult {
-			ensure!(amount >= T::MinDelegateAmount::get(), Error::<T>::BelowMinimumDelegateAmount);
+			Self::deposit_event(Event::CandidateLeavingScheduled { candidate_id: candidate, exit_epoch });
 			Ok(())
 		}
 
-		/// Add a delegator to the candidate's delegators list.
-		pub fn add_candidate_delegator(
-			candidate: &T::AccountId,
-			delegator: &T::AccountId,
+		/// Finalizes the exit of a leaving candidate once its unbonding period has passed.
+		///
+		/// Any signed origin can execute the exit on behalf of the candidate.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction.
+		/// - `candidate`: The account ID of the leaving candidate.
+		///
+		/// Errors:
+		/// - `CandidateDoesNotExist`: Raised if the candidate is not in the candidate pool.
+		/// - `CandidateNotLeaving`: Raised if the candidate did not request to leave.
+		/// - `CandidateCannotLeaveYet`: Raised if the unbonding period has not passed yet.
+		///
+		/// Effects:
+		/// - Releases the candidate bond and the holds of all its delegators.
+		/// - Removes the candidate and its delegations from storage.
+		///
+		/// Emits:
+		/// - `CandidateRegistrationRemoved`: When the candidate is removed from the pool.
+		/// - `RewardClaimed`: When the pending rewards of the candidate are paid out.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::execute_leave_candidates(T::MaxCandidateDelegators::get()))]
+		pub fn execute_leave_candidates(
+			origin: OriginFor<T>,
+			candidate: T::AccountId,
 		) -> DispatchResult {
-			// Add the delegator to the candidate's delegators list
-			let mut candidate_delegators = CandidateDelegators::<T>::get(&candidate);
-			candidate_delegators
-				.try_push(delegator.clone())
-				.map_err(|_| Error::<T>::TooManyDelegatorsInPool)?;
-			// Update the candidate's delegators list
-			CandidateDelegators::<T>::set(&candidate, candidate_delegators);
-			Ok(())
+			ensure_signed(origin)?;
+			let candidate_detail = Self::get_candidate(&candidate)?;
+			match candidate_detail.status {
+				CandidateStatus::Active => return Err(Error::<T>::CandidateNotLeaving.into()),
+				CandidateStatus::Leaving(exit_epoch) => ensure!(
+					EpochIndex::<T>::get() >= exit_epoch,
+					Error::<T>::CandidateCannotLeaveYet
+				),
+			}
+			Self::remove_candidate(&candidate)
 		}
-		
-		/// Increase the total delegated amount of the candidate.
-		fn increase_candidate_delegations(
-			candidate: &T::AccountId,
-			amount: &BalanceOf<T>,
-		) -> DispatchResultWithValue<BalanceOf<T>> {
-			// Increase the candidate's total delegated amount
+
+		/// Schedules the undelegation of a specified amount of funds from a candidate.
+		///
+		/// The amount stops counting towards the candidate's stake immediately, but it stays on
+		/// hold for `UnbondingEpochs` epochs. Once the unbonding period has passed, the funds are
+		/// released with `execute_undelegate`. A delegator can have at most one pending
+		/// undelegation per candidate.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the delegator.
+		/// - `candidate`: The account ID of the candidate to undelegate funds from.
+		/// - `amount`: The amount of funds to undelegate.
+		///
+		/// Errors:
+		/// - `InvalidZeroAmount`: Raised if `amount` is zero.
+		/// - `PendingUndelegateRequestExists`: Raised if there is already a pending undelegation
+		///   from the candidate.
+		/// - `DelegationDoesNotExist`: Raised if the delegator has not delegated to the candidate.
+		/// - `InvalidMinimumDelegateAmount`: Raised if `amount` is above the delegated amount.
+		/// - `BelowMinimumDelegateAmount`: Raised if the remaining delegation is non-zero and
+		///   below `MinDelegateAmount`.
+		/// - `TooManyUnbondingRequests`: Raised if the unbonding queue of the delegator is full.
+		///
+		/// Emits:
+		/// - `UndelegateScheduled`: When the undelegation is queued, including the candidate's
+		///   account ID (`candidate_id`), the delegator's account ID (`delegator`), the amount
+		///   (`amount`) and the epoch index from which it can be released (`unlock_epoch`).
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::schedule_undelegate(T::MaxCandidateDelegators::get()))]
+		pub fn schedule_undelegate(
+			origin: OriginFor<T>,
+			candidate: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let delegator = ensure_signed(origin)?;
+			ensure!(amount > Zero::zero(), Error::<T>::InvalidZeroAmount);
+
+			let mut unbonding_requests = UnbondingDelegations::<T>::get(&delegator);
+			ensure!(
+				!unbonding_requests.iter().any(|request| request.candidate == candidate),
+				Error::<T>::PendingUndelegateRequestExists
+			);
+
+			Self::reduce_delegation(&delegator, &candidate, amount)?;
+			Self::decrease_candidate_delegations(&candidate, &amount)?;
+
+			// The hold is kept until the request is executed
+			let unlock_epoch = EpochIndex::<T>::get().saturating_add(T::UnbondingEpochs::get());
+			unbonding_requests
+				.try_push(UnbondingRequest::new(candidate.clone(), amount, unlock_epoch))
+				.map_err(|_| Error::<T>::TooManyUnbondingRequests)?;
+			UnbondingDelegations::<T>::insert(&delegator, unbonding_requests);
+
+			Self::deposit_event(Event::UndelegateScheduled {
+				candidate_id: candidate,
+				delegator,
+				amount,
+				unlock_epoch,
+			});
+			Ok(())
+		}
+
+		/// Releases every pending undelegation of the caller whose unbonding period has passed.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the delegator.
+		///
+		/// Errors:
+		/// - `NoUnbondedDelegationFound`: Raised if none of the pending undelegations can be
+		///   released yet.
+		///
+		/// Emits:
+		/// - `UndelegateExecuted`: For each released undelegation, including the candidate's
+		///   account ID (`candidate_id`), the delegator's account ID (`delegator`) and the released
+		///   amount (`amount`).
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::execute_undelegate(T::MaxDelegateCount::get()))]
+		pub fn execute_undelegate(origin: OriginFor<T>) -> DispatchResult {
+			let delegator = ensure_signed(origin)?;
+			let epoch_index = EpochIndex::<T>::get();
+
+			let (unlocked, pending): (Vec<_>, Vec<_>) = UnbondingDelegations::<T>::get(&delegator)
+				.into_inner()
+				.into_iter()
+				.partition(|request| request.is_unlocked(epoch_index));
+			ensure!(!unlocked.is_empty(), Error::<T>::NoUnbondedDelegationFound);
+
+			for request in unlocked {
+				Self::release_delegated_amount(&delegator, &request.amount)?;
+				Self::deposit_event(Event::UndelegateExecuted {
+					candidate_id: request.candidate,
+					delegator: delegator.clone(),
+					amount: request.amount,
+				});
+			}
+
+			if pending.is_empty() {
+				UnbondingDelegations::<T>::remove(&delegator);
+			} else {
+				// The remaining requests are a subset of a bounded vector, so they always fit
+				UnbondingDelegations::<T>::insert(
+					&delegator,
+					BoundedVec::truncate_from(pending),
+				);
+			}
+			Ok(())
+		}
+
+		/// Cancels a pending undelegation and adds the amount back to the delegation.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the delegator.
+		/// - `candidate`: The account ID of the candidate the undelegation was scheduled from.
+		///
+		/// Errors:
+		/// - `UndelegateRequestDoesNotExist`: Raised if there is no pending undelegation from the
+		///   candidate.
+		/// - `CandidateDoesNotExist`: Raised if the candidate is no longer in the candidate pool.
+		/// - `CandidateIsLeaving`: Raised if the candidate requested to leave the pool.
+		/// - `TooManyCandidateDelegations`: Raised if restoring the delegation exceeds the
+		///   maximum allowed number of candidate delegations.
+		///
+		/// Emits:
+		/// - `UndelegateCancelled`: When the amount is added back, including the candidate's
+		///   account ID (`candidate_id`), the delegator's account ID (`delegator`), the restored
+		///   amount (`amount`) and the total delegated amount to the candidate
+		///   (`total_delegated_amount`).
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::cancel_undelegate(T::MaxCandidateDelegators::get()))]
+		pub fn cancel_undelegate(origin: OriginFor<T>, candidate: T::AccountId) -> DispatchResult {
+			let delegator = ensure_signed(origin)?;
+
+			let mut unbonding_requests = UnbondingDelegations::<T>::get(&delegator);
+			let request_index = unbonding_requests
+				.iter()
+				.position(|request| request.candidate == candidate)
+				.ok_or(Error::<T>::UndelegateRequestDoesNotExist)?;
+			let request = unbonding_requests.remove(request_index);
+
+			ensure!(Self::get_candidate(&candidate)?.is_active(), Error::<T>::CandidateIsLeaving);
+			// The amount is still on hold, so only the delegation records are restored
+			Self::add_delegation(&delegator, &candidate, request.amount)?;
+			let total_delegated_amount =
+				Self::increase_candidate_delegations(&candidate, &request.amount)?;
+
+			if unbonding_requests.is_empty() {
+				UnbondingDelegations::<T>::remove(&delegator);
+			} else {
+				UnbondingDelegations::<T>::insert(&delegator, unbonding_requests);
+			}
+
+			Self::deposit_event(Event::UndelegateCancelled {
+				candidate_id: candidate,
+				delegator,
+				amount: request.amount,
+				total_delegated_amount,
+			});
+			Ok(())
+		}
+
+		/// Increases the bond of the calling candidate by holding more of its funds.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the candidate.
+		/// - `amount`: The amount of funds to add to the bond.
+		///
+		/// Errors:
+		/// - `InvalidZeroAmount`: Raised if `amount` is zero.
+		/// - `CandidateDoesNotExist`: Raised if the caller is not in the candidate pool.
+		/// - `CandidateIsLeaving`: Raised if the caller requested to leave the pool.
+		///
+		/// Emits:
+		/// - `CandidateBondIncreased`: When the bond is increased, including the candidate's
+		///   account ID (`candidate_id`), the added amount (`amount`) and the new bond
+		///   (`new_bond`).
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::candidate_bond_more())]
+		pub fn candidate_bond_more(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+			let candidate = ensure_signed(origin)?;
+			ensure!(amount > Zero::zero(), Error::<T>::InvalidZeroAmount);
 			let mut candidate_detail = Self::get_candidate(&candidate)?;
+			ensure!(candidate_detail.is_active(), Error::<T>::CandidateIsLeaving);
+
+			T::NativeBalance::hold(&HoldReason::CandidateBondReserved.into(), &candidate, amount)?;
+
+			let new_bond = candidate_detail.bond.checked_add(&amount).expect("Overflow");
+			candidate_detail.update_bond(new_bond);
+			CandidatePool::<T>::set(&candidate, Some(candidate_detail));
+
+			Self::deposit_event(Event::CandidateBondIncreased { candidate_id: candidate, amount, new_bond });
+			Ok(())
+		}
+
+		/// Schedules a decrease of the bond of the calling candidate.
+		///
+		/// The amount stops counting towards the candidate's stake immediately, but it stays on
+		/// hold for `UnbondingEpochs` epochs. Once the unbonding period has passed, the funds are
+		/// released with `execute_candidate_bond_less`.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the candidate.
+		/// - `amount`: The amount of funds to remove from the bond.
+		///
+		/// Errors:
+		/// - `InvalidZeroAmount`: Raised if `amount` is zero.
+		/// - `CandidateDoesNotExist`: Raised if the caller is not in the candidate pool.
+		/// - `CandidateIsLeaving`: Raised if the caller requested to leave the pool.
+		/// - `PendingBondLessRequestExists`: Raised if a bond decrease is already pending.
+		/// - `BelowMinimumCandidateBond`: Raised if the remaining bond is below
+		///   `MinCandidateBond`.
+		///
+		/// Emits:
+		/// - `CandidateBondLessScheduled`: When the decrease is queued, including the candidate's
+		///   account ID (`candidate_id`), the amount (`amount`), the new bond (`new_bond`) and the
+		///   epoch index from which it can be released (`unlock_epoch`).
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::schedule_candidate_bond_less())]
+		pub fn schedule_candidate_bond_less(
+			origin: OriginFor<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let candidate = ensure_signed(origin)?;
+			ensure!(amount > Zero::zero(), Error::<T>::InvalidZeroAmount);
+			let mut candidate_detail = Self::get_candidate(&candidate)?;
+			ensure!(candidate_detail.is_active(), Error::<T>::CandidateIsLeaving);
+			ensure!(
+				!CandidateBondLessRequests::<T>::contains_key(&candidate),
+				Error::<T>::PendingBondLessRequestExists
+			);
+
+			let new_bond = candidate_detail
+				.bond
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::BelowMinimumCandidateBond)?;
+			ensure!(new_bond >= T::MinCandidateBond::get(), Error::<T>::BelowMinimumCandidateBond);
+			candidate_detail.update_bond(new_bond);
+			CandidatePool::<T>::set(&candidate, Some(candidate_detail));
+
+			// The hold is kept until the request is executed
+			let unlock_epoch = EpochIndex::<T>::get().saturating_add(T::UnbondingEpochs::get());
+			CandidateBondLessRequests::<T>::insert(
+				&candidate,
+				BondLessRequest::new(amount, unlock_epoch),
+			);
+
+			Self::deposit_event(Event::CandidateBondLessScheduled {
+				candidate_id: candidate,
+				amount,
+				new_bond,
+				unlock_epoch,
+			});
+			Ok(())
+		}
+
+		/// Releases the pending bond decrease of the calling candidate once its unbonding period
+		/// has passed.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the candidate.
+		///
+		/// Errors:
+		/// - `BondLessRequestDoesNotExist`: Raised if there is no pending bond decrease.
+		/// - `BondLessRequestNotUnlocked`: Raised if the unbonding period has not passed yet.
+		///
+		/// Emits:
+		/// - `CandidateBondLessExecuted`: When the amount is released, including the candidate's
+		///   account ID (`candidate_id`) and the released amount (`amount`).
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::execute_candidate_bond_less())]
+		pub fn execute_candidate_bond_less(origin: OriginFor<T>) -> DispatchResult {
+			let candidate = ensure_signed(origin)?;
+			let request = CandidateBondLessRequests::<T>::get(&candidate)
+				.ok_or(Error::<T>::BondLessRequestDoesNotExist)?;
+			ensure!(
+				request.is_unlocked(EpochIndex::<T>::get()),
+				Error::<T>::BondLessRequestNotUnlocked
+			);
+
+			Self::release_candidate_bonds(&candidate, request.amount)?;
+			CandidateBondLessRequests::<T>::remove(&candidate);
+
+			Self::deposit_event(Event::CandidateBondLessExecuted {
+				candidate_id: candidate,
+				amount: request.amount,
+			});
+			Ok(())
+		}
+
+		/// Cancels the pending bond decrease of the calling candidate and adds the amount back
+		/// to its bond.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the candidate.
+		///
+		/// Errors:
+		/// - `BondLessRequestDoesNotExist`: Raised if there is no pending bond decrease.
+		/// - `CandidateDoesNotExist`: Raised if the caller is not in the candidate pool.
+		///
+		/// Emits:
+		/// - `CandidateBondLessCancelled`: When the amount is added back, including the
+		///   candidate's account ID (`candidate_id`), the restored amount (`amount`) and the new
+		///   bond (`new_bond`).
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::cancel_candidate_bond_less())]
+		pub fn cancel_candidate_bond_less(origin: OriginFor<T>) -> DispatchResult {
+			let candidate = ensure_signed(origin)?;
+			let request = CandidateBondLessRequests::<T>::take(&candidate)
+				.ok_or(Error::<T>::BondLessRequestDoesNotExist)?;
+
+			// The amount is still on hold, so only the bond is restored
+			let mut candidate_detail = Self::get_candidate(&candidate)?;
+			let new_bond = candidate_detail.bond.checked_add(&request.amount).expect("Overflow");
+			candidate_detail.update_bond(new_bond);
+			CandidatePool::<T>::set(&candidate, Some(candidate_detail));
+
+			Self::deposit_event(Event::CandidateBondLessCancelled {
+				candidate_id: candidate,
+				amount: request.amount,
+				new_bond,
+			});
+			Ok(())
+		}
+
+		/// Sets the share of the rewards earned through a delegation that is delegated again to
+		/// the same candidate automatically.
+		///
+		/// The compounded share is held from the reward as soon as it is credited, while the rest
+		/// stays claimable through `claim_reward`.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the delegator.
+		/// - `candidate`: The account ID of the delegated candidate.
+		/// - `value`: The share of the rewards to compound.
+		///
+		/// Errors:
+		/// - `DelegationDoesNotExist`: Raised if the delegator has not delegated to the candidate.
+		///
+		/// Emits:
+		/// - `AutoCompoundSet`: When the setting is updated, including the candidate's account ID
+		///   (`candidate_id`), the delegator's account ID (`delegator`) and the new share
+		///   (`value`).
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::set_auto_compound())]
+		pub fn set_auto_compound(
+			origin: OriginFor<T>,
+			candidate: T::AccountId,
+			value: Percent,
+		) -> DispatchResult {
+			let delegator = ensure_signed(origin)?;
+			let mut delegation_info = Self::get_delegation(&delegator, &candidate)?;
+			delegation_info.set_auto_compound(value);
+			DelegationInfos::<T>::set(&delegator, &candidate, Some(delegation_info));
+
+			Self::deposit_event(Event::AutoCompoundSet { candidate_id: candidate, delegator, value });
+			Ok(())
+		}
+
+		/// Updates the commission of the calling candidate.
+		///
+		/// The commission can be changed at most once every `CommissionUpdateEpochs` epochs. The
+		/// new commission applies to block rewards from the next epoch snapshot onwards.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the candidate.
+		/// - `commission`: The new share of each block reward taken by the candidate.
+		///
+		/// Errors:
+		/// - `CommissionTooHigh`: Raised if `commission` is above `MaxCommission`.
+		/// - `CandidateDoesNotExist`: Raised if the caller is not in the candidate pool.
+		/// - `CommissionUpdateTooSoon`: Raised if the commission was updated less than
+		///   `CommissionUpdateEpochs` epochs ago.
+		///
+		/// Emits:
+		/// - `CommissionUpdated`: When the commission is updated, including the candidate's
+		///   account ID (`candidate_id`) and the new commission (`commission`).
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::set_commission())]
+		pub fn set_commission(origin: OriginFor<T>, commission: Perbill) -> DispatchResult {
+			let candidate = ensure_signed(origin)?;
+			ensure!(commission <= T::MaxCommission::get(), Error::<T>::CommissionTooHigh);
+			let mut candidate_detail = Self::get_candidate(&candidate)?;
+
+			let epoch_index = EpochIndex::<T>::get();
+			ensure!(
+				epoch_index >=
+					candidate_detail
+						.commission_updated_at
+						.saturating_add(T::CommissionUpdateEpochs::get()),
+				Error::<T>::CommissionUpdateTooSoon
+			);
+			candidate_detail.set_commission(commission, epoch_index);
+			CandidatePool::<T>::set(&candidate, Some(candidate_detail));
+
+			Self::deposit_event(Event::CommissionUpdated { candidate_id: candidate, commission });
+			Ok(())
+		}
+
+		/// Sets the profile of the calling candidate, replacing any previous profile.
+		///
+		/// A deposit of `ProfileDepositPerByte` for each byte of the profile is held from the
+		/// candidate. The deposit is adjusted when the profile is replaced and released when the
+		/// candidate leaves the pool.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be signed by the candidate.
+		/// - `display_name`: The name under which the candidate is displayed.
+		/// - `website`: The website of the candidate.
+		/// - `contact`: A way to contact the candidate.
+		/// - `p2p_address`: The p2p address of the candidate's node.
+		///
+		/// Errors:
+		/// - `CandidateDoesNotExist`: Raised if the caller is not in the candidate pool.
+		/// - `ProfileFieldTooLong`: Raised if a field is longer than `MaxProfileFieldLength`.
+		///
+		/// Effects:
+		/// - Stores the profile in `CandidateProfiles`.
+		/// - Holds the difference with the previous deposit from the candidate's account, or
+		///   releases it if the new profile is shorter.
+		///
+		/// Emits:
+		/// - `CandidateProfileSet`: When the profile is stored, including the candidate's account
+		///   ID (`candidate_id`) and the deposit held for the profile (`deposit`).
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::WeightInfo::set_candidate_profile())]
+		pub fn set_candidate_profile(
+			origin: OriginFor<T>,
+			display_name: Vec<u8>,
+			website: Vec<u8>,
+			contact: Vec<u8>,
+			p2p_address: Vec<u8>,
+		) -> DispatchResult {
+			let candidate = ensure_signed(origin)?;
+			ensure!(Self::is_candidate(&candidate), Error::<T>::CandidateDoesNotExist);
+
+			let to_field = |field: Vec<u8>| -> Result<ProfileField<T>, DispatchError> {
+				ProfileField::<T>::try_from(field)
+					.map_err(|_| Error::<T>::ProfileFieldTooLong.into())
+			};
+			let profile = CandidateProfile::<T>::new(
+				to_field(display_name)?,
+				to_field(website)?,
+				to_field(contact)?,
+				to_field(p2p_address)?,
+			);
+
+			let deposit =
+				T::ProfileDepositPerByte::get().saturating_mul(profile.byte_len().into());
+			let old_deposit = CandidateProfiles::<T>::get(&candidate)
+				.map(|(_, old_deposit)| old_deposit)
+				.unwrap_or_else(Zero::zero);
+			if deposit > old_deposit {
+				T::NativeBalance::hold(
+					&HoldReason::ProfileDeposit.into(),
+					&candidate,
+					deposit.saturating_sub(old_deposit),
+				)?;
+			} else if deposit < old_deposit {
+				T::NativeBalance::release(
+					&HoldReason::ProfileDeposit.into(),
+					&candidate,
+					old_deposit.saturating_sub(deposit),
+					Precision::BestEffort,
+				)?;
+			}
+			CandidateProfiles::<T>::insert(&candidate, (profile, deposit));
+
+			Self::deposit_event(Event::CandidateProfileSet { candidate_id: candidate, deposit });
+			Ok(())
+		}
+
+		/// Cancels deferred slashes before they are applied.
+		///
+		/// Requires the caller to have the privilege defined by `ForceOrigin`.
+		///
+		/// Parameters:
+		/// - `origin`: The origin of the transaction, which must be authorized by `ForceOrigin`.
+		/// - `epoch_index`: The epoch index in which the offences were reported.
+		/// - `slash_indices`: The sorted and unique indices of the slashes to cancel within
+		///   `UnappliedSlashes` of the epoch.
+		///
+		/// Errors:
+		/// - `EmptySlashIndices`: Raised if `slash_indices` is empty.
+		/// - `InvalidSlashIndex`: Raised if `slash_indices` is not sorted, contains duplicates or
+		///   points outside of the deferred slashes of the epoch.
+		///
+		/// Emits:
+		/// - `DeferredSlashCancelled`: For each cancelled slash, including the validator's account
+		///   ID (`validator`), the offence kind (`kind`) and the epoch index (`epoch_index`).
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::cancel_deferred_slash(slash_indices.len() as u32))]
+		pub fn cancel_deferred_slash(
+			origin: OriginFor<T>,
+			epoch_index: u32,
+			slash_indices: Vec<u32>,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(!slash_indices.is_empty(), Error::<T>::EmptySlashIndices);
+			ensure!(
+				slash_indices.windows(2).all(|pair| pair[0] < pair[1]),
+				Error::<T>::InvalidSlashIndex
+			);
+
+			let mut unapplied = UnappliedSlashes::<T>::get(epoch_index);
+			let last_index = *slash_indices.last().expect("Checked not empty above; qed");
+			ensure!((last_index as usize) < unapplied.len(), Error::<T>::InvalidSlashIndex);
+
+			// Remove from the back so that the remaining indices stay valid
+			for index in slash_indices.into_iter().rev() {
+				let slash = unapplied.remove(index as usize);
+				Self::deposit_event(Event::DeferredSlashCancelled {
+					validator: slash.validator,
+					kind: slash.kind,
+					epoch_index,
+				});
+			}
+
+			if unapplied.is_empty() {
+				UnappliedSlashes::<T>::remove(epoch_index);
+			} else {
+				UnappliedSlashes::<T>::insert(epoch_index, unapplied);
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account holding the transaction fees owed to the block authors.
+		pub fn reward_pot_account() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		// A function to get you an account id for the current block author.
+		pub fn find_author() -> Option<T::AccountId> {
+			// If you want to see a realistic example of the `FindAuthor` interface, see
+			// `pallet-authorship`.
+			T::FindAuthor::find_author::<'_, Vec<_>>(Default::default())
+		}
+	}
+
+	/// implementation of some util function the pallet
+	impl<T: Config> Pallet<T> {
+		/// Get the delegation information between a delegator and a candidate.
+		pub fn get_delegation(
+			delegator: &T::AccountId,
+			candidate: &T::AccountId,
+		) -> DispatchResultWithValue<Delegation<T>> {
+			Ok(DelegationInfos::<T>::try_get(delegator, candidate)
+				.map_err(|_| Error::<T>::DelegationDoesNotExist)?)
+		}
+
+		/// Get the candidate information from the candidate pool.
+		pub fn get_candidate(
+			candidate: &T::AccountId,
+		) -> DispatchResultWithValue<Candidate<T>> {
+			Ok(CandidatePool::<T>::try_get(candidate)
+				.map_err(|_| Error::<T>::CandidateDoesNotExist)?)
+		}
+
+		/// Check if the candidate is in the candidate pool.
+		pub fn is_candidate(validator: &T::AccountId) -> bool {
+			CandidatePool::<T>::contains_key(validator)
+		}
+
+		/// Check if the delegator has delegated to the candidate.
+		pub(crate) fn check_delegated_amount(amount: BalanceOf<T>) -> DispatchResult {
+			ensure!(amount >= T::MinDelegateAmount::get(), Error::<T>::BelowMinimumDelegateAmount);
+			Ok(())
+		}
+
+		/// Add an amount to the delegation between a delegator and a candidate, creating the
+		/// delegation record if it's the first time delegation. This does not hold any funds.
+		fn add_delegation(
+			delegator: &T::AccountId,
+			candidate: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			match DelegationInfos::<T>::try_get(delegator, candidate) {
+				// If the delegator has previously delegated to the candidate, update the delegated amount
+				Ok(mut delegation_info) => {
+					// Check if the new delegated amount will overflow
+					let new_delegated_amount =
+						delegation_info.amount.checked_add(&amount).expect("Overflow");
+					Self::check_delegated_amount(new_delegated_amount)?;
+					// Update the delegated amount
+					Self::update_candidate_delegator(
+						candidate,
+						delegator,
+						&delegation_info.amount,
+						new_delegated_amount,
+					)?;
+					delegation_info.set_amount(new_delegated_amount);
+					DelegationInfos::<T>::set(delegator, candidate, Some(delegation_info));
+				},
+				Err(_) => {
+					// If it's the first time delegation, initialize a new delegation record
+					// Check if the new delegated amount will overflow
+					Self::check_delegated_amount(amount)?;
+					let delegate_count = DelegateCountMap::<T>::get(delegator);
+					let new_delegate_count = delegate_count.saturating_add(1);
+					ensure!(
+						new_delegate_count <= T::MaxDelegateCount::get(),
+						Error::<T>::TooManyCandidateDelegations
+					);
+					// Update the delegator's delegate count
+					DelegateCountMap::<T>::set(delegator, new_delegate_count);
+					// Update the candidate's delegator list
+					Self::add_candidate_delegator(candidate, delegator, amount)?;
+					// Initialize a new delegation record
+					let new_delegation_info = Delegation::new(amount);
+					// Set the new delegation record
+					DelegationInfos::<T>::insert(delegator, candidate, new_delegation_info);
+				},
+			};
+			Ok(())
+		}
+
+		/// Subtract an amount from the delegation between a delegator and a candidate, removing
+		/// the delegation record if nothing is left. This does not release any funds.
+		fn reduce_delegation(
+			delegator: &T::AccountId,
+			candidate: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithValue<BalanceOf<T>> {
+			let mut delegation_info = Self::get_delegation(delegator, candidate)?;
+			let new_delegated_amount = delegation_info
+				.amount
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::InvalidMinimumDelegateAmount)?;
+
+			if new_delegated_amount.is_zero() {
+				// If the delegated amount is removed completely, we want to remove
+				// related information to the delegation betwene (delegator, candidate)
+				Self::remove_candidate_delegation_data(delegator, candidate)?;
+			} else {
+				// Remove the delegated amoutn partially but makes sure it is still above
+				// the minimum delegated amount
+				Self::check_delegated_amount(new_delegated_amount)?;
+
+				Self::update_candidate_delegator(
+					candidate,
+					delegator,
+					&delegation_info.amount,
+					new_delegated_amount,
+				)?;
+				delegation_info.set_amount(new_delegated_amount);
+				DelegationInfos::<T>::set(delegator, candidate, Some(delegation_info));
+			}
+			Ok(new_delegated_amount)
+		}
+
+		/// Add a delegator to the candidate's delegators list, which is ordered from the highest
+		/// to the lowest delegated amount. If the list is full, the lowest delegator is kicked when
+		/// the new delegation is higher.
+		pub fn add_candidate_delegator(
+			candidate: &T::AccountId,
+			delegator: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let candidate_delegators = CandidateDelegators::<T>::get(candidate);
+			if candidate_delegators.is_full() {
+				let (lowest_delegator, lowest_amount) = candidate_delegators
+					.lowest()
+					.cloned()
+					.ok_or(Error::<T>::TooManyDelegatorsInPool)?;
+				ensure!(amount > lowest_amount, Error::<T>::TooManyDelegatorsInPool);
+				Self::kick_delegator(candidate, &lowest_delegator)?;
+			}
+
+			// Add the delegator to the candidate's delegators list
+			CandidateDelegators::<T>::try_mutate(candidate, |candidate_delegators| {
+				candidate_delegators
+					.insert(delegator.clone(), amount)
+					.then_some(())
+					.ok_or(Error::<T>::TooManyDelegatorsInPool.into())
+			})
+		}
+
+		/// Move a delegator to its new position in the candidate's delegators list after its
+		/// delegated amount changed from `old_amount` to `new_amount`.
+		pub(crate) fn update_candidate_delegator(
+			candidate: &T::AccountId,
+			delegator: &T::AccountId,
+			old_amount: &BalanceOf<T>,
+			new_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			CandidateDelegators::<T>::try_mutate(candidate, |candidate_delegators| {
+				candidate_delegators
+					.update(delegator, old_amount, new_amount)
+					.then_some(())
+					.ok_or(Error::<T>::DelegationDoesNotExist.into())
+			})
+		}
+
+		/// Kick a delegator from a candidate. The delegated amount goes through the unbonding
+		/// queue like an undelegation, so that it can still be slashed for the offences of the
+		/// epochs it was exposed in. It is added to a pending undelegation from the same
+		/// candidate, if any.
+		fn kick_delegator(candidate: &T::AccountId, delegator: &T::AccountId) -> DispatchResult {
+			let delegation_info = Self::get_delegation(delegator, candidate)?;
+			let amount = delegation_info.amount;
+			Self::remove_candidate_delegation_data(delegator, candidate)?;
+			Self::decrease_candidate_delegations(candidate, &amount)?;
+
+			// The hold is kept until the request is executed
+			let unlock_epoch = EpochIndex::<T>::get().saturating_add(T::UnbondingEpochs::get());
+			let mut unbonding_requests = UnbondingDelegations::<T>::get(delegator);
+			match unbonding_requests.iter_mut().find(|request| &request.candidate == candidate) {
+				Some(request) => {
+					request.amount = request.amount.saturating_add(amount);
+					request.unlock_epoch = unlock_epoch;
+				},
+				None => unbonding_requests
+					.try_push(UnbondingRequest::new(candidate.clone(), amount, unlock_epoch))
+					.map_err(|_| Error::<T>::TooManyUnbondingRequests)?,
+			}
+			UnbondingDelegations::<T>::insert(delegator, unbonding_requests);
+
+			Self::deposit_event(Event::DelegatorKicked {
+				candidate_id: candidate.clone(),
+				delegator: delegator.clone(),
+				amount,
+				unlock_epoch,
+			});
+			Ok(())
+		}
+		
+		/// Increase the total delegated amount of the candidate.
+		fn increase_candidate_delegations(
+			candidate: &T::AccountId,
+			amount: &BalanceOf<T>,
+		) -> DispatchResultWithValue<BalanceOf<T>> {
+			// Increase the candidate's total delegated amount
+			let mut candidate_detail = Self::get_candidate(candidate)?;
 			let total_delegated_amount = candidate_detail.add_delegated_amount(*amount)?;
 			// Update the candidate's total delegated amount
-			CandidatePool::<T>::set(&candidate, Some(candidate_detail));
+			CandidatePool::<T>::set(candidate, Some(candidate_detail));
 
 			Ok(total_delegated_amount)
 		}
 
 		/// Decrease the total delegated amount of the candidate.
-		fn decrease_candidate_delegations(
+		pub(crate) fn decrease_candidate_delegations(
 			candidate: &T::AccountId,
 			amount: &BalanceOf<T>,
 		) -> DispatchResultWithValue<BalanceOf<T>> {
-			let mut candidate_detail = Self::get_candidate(&candidate)?;
+			let mut candidate_detail = Self::get_candidate(candidate)?;
 			// Decrease the candidate's total delegated amount
 			let total_delegated_amount = candidate_detail.sub_delegated_amount(*amount)?;
-			CandidatePool::<T>::set(&candidate, Some(candidate_detail));
+			CandidatePool::<T>::set(candidate, Some(candidate_detail));
 
 			Ok(total_delegated_amount)
 		}
@@ -735,13 +1935,30 @@ pub mod pallet {
 			// Releasing the hold balance amount of candidate
 			T::NativeBalance::release(
 				&HoldReason::CandidateBondReserved.into(),
-				&candidate,
+				candidate,
 				bond,
 				Precision::BestEffort,
 			)?;
 			Ok(())
 		}
 
+		/// Remove the profile of a candidate and release its deposit.
+		fn clear_candidate_profile(candidate: &T::AccountId) -> DispatchResult {
+			if let Some((_, deposit)) = CandidateProfiles::<T>::take(candidate) {
+				T::NativeBalance::release(
+					&HoldReason::ProfileDeposit.into(),
+					candidate,
+					deposit,
+					Precision::BestEffort,
+				)?;
+				Self::deposit_event(Event::CandidateProfileCleared {
+					candidate_id: candidate.clone(),
+					deposit,
+				});
+			}
+			Ok(())
+		}
+
 		/// Releasing the hold balance amount of delegator
 		fn release_delegated_amount(
 			delegator: &T::AccountId,
@@ -749,7 +1966,7 @@ pub mod pallet {
 		) -> DispatchResult {
 			T::NativeBalance::release(
 				&HoldReason::DelegateAmountReserved.into(),
-				&delegator,
+				delegator,
 				*amount,
 				Precision::BestEffort,
 			)?;
@@ -757,59 +1974,116 @@ pub mod pallet {
 		}
 
 		/// Remove the delegation information between a delegator and a candidate.
-		fn remove_candidate_delegation_data(
+		pub(crate) fn remove_candidate_delegation_data(
 			delegator: &T::AccountId,
 			candidate: &T::AccountId,
 		) -> DispatchResult {
 			// Remove the delegation information between the delegator and the candidate
-			DelegationInfos::<T>::remove(&delegator, &candidate);
+			let delegation_info = DelegationInfos::<T>::take(delegator, candidate)
+				.ok_or(Error::<T>::DelegationDoesNotExist)?;
 			// Decrease the delegator's delegate count
-			let delegate_count = DelegateCountMap::<T>::get(&delegator);
-			DelegateCountMap::<T>::set(&delegator, delegate_count.saturating_sub(1));
-
-			// Remove delegator from the candidate delegators vector
-			let mut candidate_delegators = CandidateDelegators::<T>::get(&candidate);
-			// find the delegator in the candidate delegators list and remove it
-			candidate_delegators
-				.binary_search(&delegator)
-				.map_err(|_| Error::<T>::DelegationDoesNotExist)
-				.map(|indx| candidate_delegators.remove(indx))?;
-			CandidateDelegators::<T>::set(&candidate, candidate_delegators);
+			let delegate_count = DelegateCountMap::<T>::get(delegator);
+			DelegateCountMap::<T>::set(delegator, delegate_count.saturating_sub(1));
+
+			// Remove delegator from the candidate delegators index
+			CandidateDelegators::<T>::try_mutate(candidate, |candidate_delegators| {
+				candidate_delegators
+					.remove(delegator, &delegation_info.amount)
+					.then_some(())
+					.ok_or(Error::<T>::DelegationDoesNotExist.into())
+			})
+		}
+
+		/// Remove a candidate from the pool, releasing the holds of the candidate and all of its
+		/// delegators and paying out its pending rewards.
+		fn remove_candidate(candidate: &T::AccountId) -> DispatchResult {
+			let candidate_delegators = CandidateDelegators::<T>::get(candidate);
+
+			// Processing all the delegators of the candidate
+			for (delegator, amount) in candidate_delegators.into_inner() {
+				// Trying to release all the hold amount of the delegators
+				Self::release_delegated_amount(&delegator, &amount)?;
+
+				// Removing any information related to the delegation between (candidate, delegator).
+				// The delegators index is removed as a whole below rather than once per delegator.
+				DelegationInfos::<T>::remove(&delegator, candidate);
+				DelegateCountMap::<T>::mutate(&delegator, |delegate_count| {
+					*delegate_count = delegate_count.saturating_sub(1)
+				});
+			}
+			CandidateDelegators::<T>::remove(candidate);
+
+			// Releasing the hold bonds of the candidate, including a pending bond decrease
+			let candidate_detail = Self::get_candidate(candidate)?;
+			Self::release_candidate_bonds(candidate, candidate_detail.bond)?;
+			if let Some(request) = CandidateBondLessRequests::<T>::take(candidate) {
+				Self::release_candidate_bonds(candidate, request.amount)?;
+			}
+			// Claiming the rewards of the candidate
+			let rewards = Rewards::<T>::get(candidate);
+			if rewards > Zero::zero() {
+				// Mint the rewards to the candidate
+				let _ = T::NativeBalance::mint_into(candidate, rewards);
+				// Remove the rewards from the storage
+				Rewards::<T>::remove(candidate);
+				Self::deposit_event(Event::RewardClaimed { claimer: candidate.clone(), total_reward: rewards });
+			}
+			// Removing the profile of the candidate and releasing its deposit
+			Self::clear_candidate_profile(candidate)?;
+			// Removing any information related the registration of the candidate in the pool
+			CandidatePool::<T>::remove(candidate);
+
+			Self::deposit_event(Event::CandidateRegistrationRemoved { candidate_id: candidate.clone() });
 
 			Ok(())
 		}
 
 		/// Select the validator set for the next epoch.
 		pub(crate) fn select_validator_set() -> TopCandidateVec<T> {
+			let validator_len = T::MaxValidators::get();
+
+			let mut top_candidates = Self::eligible_candidates();
+
 			// If the number of candidates is below the threshold for active set, network won't
 			// function
-			if CandidatePool::<T>::count() < T::MinValidators::get() {
+			if (top_candidates.len() as u32) < T::MinValidators::get() {
 				return vec![];
 			}
-			let validator_len = T::MaxValidators::get();
-			
-			// Collect candidates with their total stake (bond + total delegations)
-			let mut top_candidates: TopCandidateVec<T> = CandidatePool::<T>::iter()
-				.map(|(candidate_id, candidate)| {
-					let total_stake = candidate.total();
-					(candidate_id, candidate.bond, total_stake)
-				})
-				.collect();
 
-			// Sort candidates by their total stake in descending order
-			top_candidates.sort_by_key(|&(_, _, total_stake)| Reverse(total_stake));
+			Self::rank_candidates(&mut top_candidates);
 
 			// Select the top candidates based on the maximum active validators allowed
 			let usize_validator_len = validator_len as usize;
 			top_candidates.into_iter().take(usize_validator_len).collect()
 		}
 
+		/// Collect the active candidates with their bond and total stake (bond + total
+		/// delegations). Candidates leaving the pool are not eligible for the next validator set.
+		pub(crate) fn eligible_candidates() -> TopCandidateVec<T> {
+			CandidatePool::<T>::iter()
+				.filter(|(_, candidate)| candidate.is_active())
+				.map(|(candidate_id, candidate)| {
+					let total_stake = candidate.total();
+					(candidate_id, candidate.bond, total_stake)
+				})
+				.collect()
+		}
+
+		/// Sort candidates by their total stake in descending order.
+		pub(crate) fn rank_candidates(candidates: &mut TopCandidateVec<T>) {
+			candidates.sort_by_key(|&(_, _, total_stake)| Reverse(total_stake));
+		}
+
 		/// Move to the next epoch.
 		pub(crate) fn move_to_next_epoch(valivdator_set: TopCandidateVec<T>) {
 			// Increment the epoch index
 			let epoch_index = EpochIndex::<T>::get();
 			let next_epoch_index = epoch_index.saturating_add(1);
 			EpochIndex::<T>::set(next_epoch_index);
+			// Offences can only be reported against the exposures of the running epoch, so the
+			// reports are bounded by one per offence kind and exposed validator
+			let _ = OffenceReports::<T>::clear_prefix(epoch_index, u32::MAX, None);
+			Self::clear_exposures(epoch_index);
 
 			Self::deposit_event(Event::NextEpochMoved {
 				last_epoch: epoch_index,
@@ -825,65 +2099,240 @@ pub mod pallet {
 			T::ReportNewValidatorSet::report_new_validator_set(new_set);
 		}
 
-		/// Capture the snapshot of the current epoch.
-		pub fn capture_epoch_snapshot(
-			validator_set: &TopCandidateVec<T>,
-		) -> Epoch<T> {
-			let mut epoch_snapshot = Epoch::<T>::default();
-			// Add the validators and their total bond to the snapshot
-			for (validator_id, bond, _) in validator_set.to_vec().iter() {
-				epoch_snapshot.add_validator(validator_id.clone(), bond.clone());
-				// Add the delegators and their delegated amount to the snapshot
-				for delegator in CandidateDelegators::<T>::get(validator_id) {
-					if let Some(delegation_info) =
-						DelegationInfos::<T>::get(&delegator, &validator_id)
-					{
-						epoch_snapshot.add_delegator(
-							delegator,
-							validator_id.clone(),
-							delegation_info.amount,
-						);
-					}
-				}
+		/// Capture the stake backing each validator of the set for the given epoch.
+		pub fn capture_exposures(epoch_index: u32, validator_set: &TopCandidateVec<T>) {
+			for (validator_id, bond, _) in validator_set.iter() {
+				Self::capture_exposure(epoch_index, validator_id, *bond);
 			}
-			// Return the snapshot of the current epoch
-			epoch_snapshot
 		}
 
-		/// Execute the rewards calculation for the last epoch block.
-		fn execute_rewards() {
+		/// Capture the stake backing a validator for the given epoch, splitting its delegators
+		/// in pages of `MaxExposurePageSize`. Returns the number of captured delegators.
+		pub(crate) fn capture_exposure(
+			epoch_index: u32,
+			validator_id: &T::AccountId,
+			bond: BalanceOf<T>,
+		) -> u32 {
+			let commission = CandidatePool::<T>::get(validator_id)
+				.map(|candidate| candidate.commission)
+				.unwrap_or_default();
+			let mut exposure = ExposureOverview::<T>::new(bond, commission);
+
+			let delegators = CandidateDelegators::<T>::get(validator_id).into_inner();
+			let page_size = T::MaxExposurePageSize::get().max(1) as usize;
+			for (page_index, page) in delegators.chunks(page_size).enumerate() {
+				exposure.total = page
+					.iter()
+					.fold(exposure.total, |total, (_, amount)| total.saturating_add(*amount));
+				exposure.delegator_count.saturating_accrue(page.len() as u32);
+				exposure.page_count.saturating_inc();
+				EpochExposurePages::<T>::insert(
+					(epoch_index, validator_id, page_index as u32),
+					ExposurePage::<T>::truncate_from(page.to_vec()),
+				);
+			}
+			EpochExposures::<T>::insert(epoch_index, validator_id, exposure);
+			delegators.len() as u32
+		}
+
+		/// Remove the exposures captured for the given epoch.
+		pub(crate) fn clear_exposures(epoch_index: u32) {
+			// Bounded by `MaxValidators` overviews and their pages
+			let _ = EpochExposures::<T>::clear_prefix(epoch_index, u32::MAX, None);
+			let _ = EpochExposurePages::<T>::clear_prefix((epoch_index,), u32::MAX, None);
+		}
+
+		/// The delegators backing a validator in the given epoch, over all its pages.
+		pub(crate) fn exposure_delegators(
+			epoch_index: u32,
+			validator_id: &T::AccountId,
+			exposure: &ExposureOverview<T>,
+		) -> Vec<(T::AccountId, BalanceOf<T>)> {
+			(0..exposure.page_count)
+				.flat_map(|page_index| {
+					EpochExposurePages::<T>::get((epoch_index, validator_id, page_index))
+						.into_inner()
+				})
+				.collect()
+		}
+
+		/// Execute the rewards calculation for the last epoch block. Returns the weight of the
+		/// payout, which grows with the delegators of the block author.
+		fn execute_rewards() -> Weight {
 			// Get the current block author
 			if let Some(current_block_author) = Self::find_author() {
-				// Get the snapshot of the last epoch
-				if let Some(Epoch { validators, delegations }) = LastEpochSnapshot::<T>::get() {
-					// Calculate the rewards for the block author and the delegators
-					if let Some(total_bond) = validators.get(&current_block_author) {
-						// Calculating the new reward of the block author
-						// The reward is calculated as 5% of the total bond of the block author
-						// The reward is distributed to the block author and the delegators
-						// based on the amount delegated to the block author.						
-						let bond = Percent::from_rational(5 as u32, 100) * Percent::from_rational(1000 as u32, 1000) * *total_bond;
-						let mut rewards = Rewards::<T>::get(&current_block_author);
-						rewards = rewards.saturating_add(bond);
-						Rewards::<T>::set(current_block_author.clone(), rewards);
-						// Calculate the rewards for the delegators
-						// The reward is calculated as 5% of the total bond of the block author
-						// The reward is distributed to the block author and the delegators
-						// based on the amount delegated to the block author.
-						for ((delegator, candidate), amount) in delegations.iter() {
-							if *candidate != current_block_author {
-								continue;
-							}
-							// Calculating the new reward of the block author
-							let bond = Percent::from_rational(5 as u32, 100) * Percent::from_rational(1000 as u32, 1000) * *amount;
-							let mut rewards = Rewards::<T>::get(&delegator);
-							rewards = rewards.saturating_add(bond);
-							// Update the rewards for the delegator
-							Rewards::<T>::set(delegator, rewards);
-						}						
-					}
+				let epoch_index = EpochIndex::<T>::get();
+				// Only the exposure of the block author is read
+				if let Some(exposure) =
+					EpochExposures::<T>::get(epoch_index, &current_block_author)
+				{
+					// The block reward is decided by the configured reward curve from the total
+					// stake backing the block author
+					let block_reward = T::RewardCurve::block_reward(exposure.total);
+					Self::distribute_block_reward(
+						epoch_index,
+						&current_block_author,
+						&exposure,
+						block_reward,
+					);
+					return T::WeightInfo::on_initialize_block_reward(exposure.delegator_count);
+				}
+			}
+			T::WeightInfo::on_initialize_block_reward(0)
+		}
+
+		/// Count the current block for its author if the author is in the active validator set.
+		/// Returns the weight of the count.
+		fn note_authored_block() -> Weight {
+			if let Some(current_block_author) = Self::find_author() {
+				let is_validator = CurrentValidators::<T>::get()
+					.iter()
+					.any(|(validator, _, _)| *validator == current_block_author);
+				if is_validator {
+					AuthoredBlocks::<T>::mutate(&current_block_author, |blocks| {
+						*blocks = blocks.saturating_add(1)
+					});
+				}
+			}
+			T::DbWeight::get().reads_writes(2, 1)
+		}
+
+		/// Split the epoch reward budget between the validators of the ending epoch in proportion
+		/// to the blocks they authored, then between each validator and its delegators.
+		fn execute_epoch_rewards() {
+			let authored_blocks: Vec<(T::AccountId, u32)> = AuthoredBlocks::<T>::drain().collect();
+			let total_blocks = authored_blocks
+				.iter()
+				.fold(0u32, |total, (_, blocks)| total.saturating_add(*blocks));
+			if total_blocks.is_zero() {
+				return;
+			}
+
+			let epoch_index = EpochIndex::<T>::get();
+			let budget = T::EpochRewardBudget::get();
+			let mut total_reward: BalanceOf<T> = Zero::zero();
+			for (validator_id, blocks) in authored_blocks {
+				if let Some(exposure) = EpochExposures::<T>::get(epoch_index, &validator_id) {
+					let validator_reward = Perbill::from_rational(blocks, total_blocks) * budget;
+					Self::distribute_block_reward(
+						epoch_index,
+						&validator_id,
+						&exposure,
+						validator_reward,
+					);
+					total_reward = total_reward.saturating_add(validator_reward);
 				}
 			}
+
+			Self::deposit_event(Event::EpochRewardsDistributed {
+				epoch_index: EpochIndex::<T>::get(),
+				total_blocks,
+				total_reward,
+			});
+		}
+
+		/// Distribute a block reward between a validator and its delegators.
+		///
+		/// The `TreasuryCut` is minted to the treasury first. The validator then takes its
+		/// commission off the top of the remaining reward, and the rest is split between the
+		/// validator bond and the delegations in proportion to their stake in the exposure of the
+		/// epoch. Only the exposure pages of the validator are read.
+		pub(crate) fn distribute_block_reward(
+			epoch_index: u32,
+			validator_id: &T::AccountId,
+			exposure: &ExposureOverview<T>,
+			block_reward: BalanceOf<T>,
+		) {
+			let total_stake = exposure.total;
+			if total_stake.is_zero() || block_reward.is_zero() {
+				return;
+			}
+
+			let treasury_reward = T::TreasuryCut::get() * block_reward;
+			if !treasury_reward.is_zero() {
+				let _ = T::NativeBalance::mint_into(&T::TreasuryAccount::get(), treasury_reward);
+			}
+			let block_reward = block_reward.saturating_sub(treasury_reward);
+
+			let commission = exposure.commission * block_reward;
+			let stakers_reward = block_reward.saturating_sub(commission);
+
+			let validator_reward = commission
+				.saturating_add(Perbill::from_rational(exposure.bond, total_stake) * stakers_reward);
+			Self::add_reward(validator_id, validator_reward);
+
+			for page_index in 0..exposure.page_count {
+				let page = EpochExposurePages::<T>::get((epoch_index, validator_id, page_index));
+				for (delegator, amount) in page.iter() {
+					let delegator_reward =
+						Perbill::from_rational(*amount, total_stake) * stakers_reward;
+					Self::credit_delegator_reward(delegator, validator_id, delegator_reward);
+				}
+			}
+		}
+
+		/// Credit the reward of a delegator, compounding the share configured for the delegation
+		/// and adding the rest to its claimable rewards.
+		fn credit_delegator_reward(
+			delegator: &T::AccountId,
+			candidate: &T::AccountId,
+			reward: BalanceOf<T>,
+		) {
+			// If the reward can't be compounded, it stays claimable in full
+			let compounded = frame_support::storage::with_storage_layer(|| {
+				Self::compound_reward(delegator, candidate, reward)
+			})
+			.unwrap_or_else(|_| Zero::zero());
+			Self::add_reward(delegator, reward.saturating_sub(compounded));
+		}
+
+		/// Delegate the auto-compounded share of a reward again to the candidate. Returns the
+		/// compounded amount.
+		fn compound_reward(
+			delegator: &T::AccountId,
+			candidate: &T::AccountId,
+			reward: BalanceOf<T>,
+		) -> DispatchResultWithValue<BalanceOf<T>> {
+			let mut delegation_info = Self::get_delegation(delegator, candidate)?;
+			let amount = delegation_info.auto_compound * reward;
+			if amount.is_zero() {
+				return Ok(Zero::zero());
+			}
+			ensure!(Self::get_candidate(candidate)?.is_active(), Error::<T>::CandidateIsLeaving);
+
+			// Rewards are minted when credited to the delegation instead of when claimed
+			T::NativeBalance::mint_into(delegator, amount)?;
+			T::NativeBalance::hold(&HoldReason::DelegateAmountReserved.into(), delegator, amount)?;
+
+			let new_delegated_amount = delegation_info
+				.amount
+				.checked_add(&amount)
+				.ok_or(sp_runtime::ArithmeticError::Overflow)?;
+			Self::update_candidate_delegator(
+				candidate,
+				delegator,
+				&delegation_info.amount,
+				new_delegated_amount,
+			)?;
+			delegation_info.set_amount(new_delegated_amount);
+			DelegationInfos::<T>::set(delegator, candidate, Some(delegation_info));
+			let total_delegated_amount = Self::increase_candidate_delegations(candidate, &amount)?;
+
+			Self::deposit_event(Event::RewardCompounded {
+				candidate_id: candidate.clone(),
+				delegator: delegator.clone(),
+				amount,
+				total_delegated_amount,
+			});
+			Ok(amount)
+		}
+
+		/// Add an amount to the claimable rewards of an account.
+		pub(crate) fn add_reward(who: &T::AccountId, amount: BalanceOf<T>) {
+			if amount.is_zero() {
+				return;
+			}
+			Rewards::<T>::mutate(who, |rewards| *rewards = rewards.saturating_add(amount));
 		}
 	}
 }
\ No newline at end of file
@@ -0,0 +1,9 @@
+//! Storage migrations of the pallet.
+//!
+//! Each version has its own module exposing an unchecked migration, which assumes the storage is
+//! at the previous version, and a [`VersionedMigration`] wrapping it. The runtime only runs the
+//! versioned wrappers: they skip the migration when the on-chain storage version does not match
+//! and bump the version once it is done.
+//!
+//! [`VersionedMigration`]: frame_support::migrations::VersionedMigration
+pub mod v1;
@@ -0,0 +1,270 @@
+//! Migration from the unversioned storage to version 1.
+//!
+//! The unversioned layout stored a [`Candidate`] as its bond and delegated total only, a
+//! [`Delegation`] as its amount only, the delegators of a candidate as a list of accounts, and
+//! the stake of the running epoch in a single `LastEpochSnapshot`. This migration:
+//!
+//! - extends every candidate with an active status and a zero commission, as if it registered at
+//!   the upgrade;
+//! - extends every delegation with a zero auto-compound share;
+//! - rebuilds the ordered [`DelegatorIndex`] of every candidate from its delegations;
+//! - replaces `LastEpochSnapshot` with the exposures of the running epoch, so that its rewards and
+//!   offences are handled as if it started after the upgrade.
+use crate::{models::*, pallet::*};
+use codec::{Decode, Encode};
+use frame_support::{
+	migrations::VersionedMigration,
+	pallet_prelude::*,
+	sp_runtime::{Perbill, Saturating},
+	storage_alias,
+	traits::UncheckedOnRuntimeUpgrade,
+};
+#[cfg(feature = "try-runtime")]
+use frame_support::sp_runtime::traits::Zero;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+/// The storage items and types of the unversioned layout.
+mod v0 {
+	use super::*;
+
+	/// A candidate before the commission and the status were added.
+	#[derive(Encode, Decode)]
+	pub struct Candidate<Balance> {
+		pub bond: Balance,
+		pub sum_delegation: Balance,
+	}
+
+	/// A delegation before the auto-compound share was added.
+	#[derive(Encode, Decode)]
+	pub struct Delegation<Balance> {
+		pub amount: Balance,
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[storage_alias]
+	pub type CandidatePool<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		Candidate<BalanceOf<T>>,
+	>;
+
+	#[cfg(feature = "try-runtime")]
+	#[storage_alias]
+	pub type DelegationInfos<T: Config> = StorageDoubleMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		Delegation<BalanceOf<T>>,
+	>;
+
+	/// The value is never decoded, the item is only removed.
+	#[storage_alias]
+	pub type LastEpochSnapshot<T: Config> = StorageValue<Pallet<T>, ()>;
+}
+
+/// The totals recorded before the migration and checked after it.
+#[cfg(feature = "try-runtime")]
+#[derive(Encode, Decode)]
+struct Totals<Balance> {
+	candidate_count: u32,
+	delegation_count: u32,
+	total_bond: Balance,
+	total_delegated: Balance,
+	total_delegations: Balance,
+}
+
+/// The number of exposure pages written for `validator_count` validators with full delegators
+/// indexes. The last page of a validator is counted even if it is partial.
+fn max_exposure_pages<T: Config>(validator_count: u64) -> u64 {
+	let page_size = T::MaxExposurePageSize::get().max(1) as u64;
+	validator_count.saturating_mul((T::MaxCandidateDelegators::get() as u64).div_ceil(page_size))
+}
+
+/// Migrate the storage from the unversioned layout to version 1, without checking the storage
+/// version. Use [`MigrateV0ToV1`] instead.
+pub struct UncheckedMigrateV0ToV1<T>(PhantomData<T>);
+
+impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateV0ToV1<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let epoch_index = EpochIndex::<T>::get();
+		let mut reads = 1u64;
+		let mut writes = 0u64;
+
+		CandidatePool::<T>::translate::<v0::Candidate<BalanceOf<T>>, _>(|_, old| {
+			reads.saturating_inc();
+			writes.saturating_inc();
+			let mut candidate = Candidate::<T>::new(old.bond, Perbill::zero(), epoch_index);
+			candidate.sum_delegation = old.sum_delegation;
+			Some(candidate)
+		});
+
+		DelegationInfos::<T>::translate::<v0::Delegation<BalanceOf<T>>, _>(|_, _, old| {
+			reads.saturating_inc();
+			writes.saturating_inc();
+			Some(Delegation::new(old.amount))
+		});
+
+		// The old lists don't hold the delegated amounts, the indexes are rebuilt from scratch
+		let cleared = CandidateDelegators::<T>::clear(u32::MAX, None);
+		writes.saturating_accrue(cleared.unique.into());
+		for (delegator, candidate_id, delegation) in DelegationInfos::<T>::iter() {
+			// Bounded by the same `MaxCandidateDelegators` as the old lists
+			let _ = CandidateDelegators::<T>::mutate(&candidate_id, |delegators| {
+				delegators.insert(delegator, delegation.amount)
+			});
+			reads.saturating_accrue(2);
+			writes.saturating_inc();
+		}
+
+		v0::LastEpochSnapshot::<T>::kill();
+		let validator_set = CurrentValidators::<T>::get().into_inner();
+		Pallet::<T>::capture_exposures(epoch_index, &validator_set);
+		reads.saturating_inc();
+		writes.saturating_inc();
+		// Each exposure reads its candidate and delegators, then writes its overview and pages
+		let pages = max_exposure_pages::<T>(validator_set.len() as u64);
+		reads.saturating_accrue(2 * validator_set.len() as u64);
+		writes.saturating_accrue(validator_set.len() as u64 + pages);
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+		let mut totals = Totals::<BalanceOf<T>> {
+			candidate_count: 0,
+			delegation_count: 0,
+			total_bond: Zero::zero(),
+			total_delegated: Zero::zero(),
+			total_delegations: Zero::zero(),
+		};
+		for (_, candidate) in v0::CandidatePool::<T>::iter() {
+			totals.candidate_count.saturating_inc();
+			totals.total_bond.saturating_accrue(candidate.bond);
+			totals.total_delegated.saturating_accrue(candidate.sum_delegation);
+		}
+		for (_, _, delegation) in v0::DelegationInfos::<T>::iter() {
+			totals.delegation_count.saturating_inc();
+			totals.total_delegations.saturating_accrue(delegation.amount);
+		}
+		Ok(totals.encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+		let totals = Totals::<BalanceOf<T>>::decode(&mut &state[..])
+			.map_err(|_| "The state recorded before the migration can't be decoded")?;
+
+		let mut total_bond: BalanceOf<T> = Zero::zero();
+		let mut total_delegated: BalanceOf<T> = Zero::zero();
+		for (_, candidate) in CandidatePool::<T>::iter() {
+			ensure!(candidate.is_active(), "A migrated candidate must be active");
+			ensure!(candidate.commission.is_zero(), "A migrated candidate has no commission");
+			total_bond.saturating_accrue(candidate.bond);
+			total_delegated.saturating_accrue(candidate.sum_delegation);
+		}
+		ensure!(
+			CandidatePool::<T>::count() == totals.candidate_count,
+			"Every candidate must be migrated"
+		);
+		ensure!(total_bond == totals.total_bond, "The bonds must be kept");
+		ensure!(total_delegated == totals.total_delegated, "The delegated totals must be kept");
+
+		let mut delegation_count = 0u32;
+		let mut total_delegations: BalanceOf<T> = Zero::zero();
+		for (_, _, delegation) in DelegationInfos::<T>::iter() {
+			ensure!(delegation.auto_compound.is_zero(), "A migrated delegation has no compounding");
+			delegation_count.saturating_inc();
+			total_delegations.saturating_accrue(delegation.amount);
+		}
+		ensure!(delegation_count == totals.delegation_count, "Every delegation must be migrated");
+		ensure!(total_delegations == totals.total_delegations, "The delegations must be kept");
+
+		let epoch_index = EpochIndex::<T>::get();
+		for (validator_id, _, _) in CurrentValidators::<T>::get() {
+			ensure!(
+				EpochExposures::<T>::contains_key(epoch_index, &validator_id),
+				"Every validator of the running epoch must be exposed"
+			);
+		}
+
+		// The delegators indexes, delegated totals and holds must agree with the delegations
+		Pallet::<T>::do_try_state()
+	}
+}
+
+/// Migrate the storage from the unversioned layout to version 1 if the on-chain storage version
+/// is 0.
+pub type MigrateV0ToV1<T> = VersionedMigration<
+	0,
+	1,
+	UncheckedMigrateV0ToV1<T>,
+	Pallet<T>,
+	<T as frame_system::Config>::DbWeight,
+>;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::*;
+	use frame_support::{
+		assert_ok,
+		storage::unhashed,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::RuntimeDbWeight,
+	};
+
+	/// Rewrite the state of the mock runtime in the unversioned layout.
+	fn write_unversioned_layout() {
+		StorageVersion::new(0).put::<Dpos>();
+		unhashed::put(
+			&CandidatePool::<Test>::hashed_key_for(1),
+			&v0::Candidate::<Balance> { bond: 1_000, sum_delegation: 500 },
+		);
+		unhashed::put(
+			&DelegationInfos::<Test>::hashed_key_for(10, 1),
+			&v0::Delegation::<Balance> { amount: 500 },
+		);
+		let delegators: Vec<AccountId> = vec![10];
+		unhashed::put(&CandidateDelegators::<Test>::hashed_key_for(1), &delegators);
+		Pallet::<Test>::clear_exposures(0);
+		v0::LastEpochSnapshot::<Test>::put(());
+	}
+
+	#[test]
+	fn migrates_the_unversioned_layout() {
+		ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+			assert_ok!(Dpos::delegate(RuntimeOrigin::signed(10), 1, 500));
+			write_unversioned_layout();
+
+			MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+			assert_eq!(Dpos::on_chain_storage_version(), StorageVersion::new(1));
+			let mut candidate = Candidate::<Test>::new(1_000, Perbill::zero(), 0);
+			candidate.sum_delegation = 500;
+			assert_eq!(CandidatePool::<Test>::get(1), Some(candidate));
+			assert_eq!(CandidatePool::<Test>::count(), 1);
+			assert_eq!(DelegationInfos::<Test>::get(10, 1), Some(Delegation::new(500)));
+			assert_eq!(CandidateDelegators::<Test>::get(1).into_inner(), vec![(10, 500)]);
+			assert!(EpochExposures::<Test>::contains_key(0, 1));
+			assert!(!v0::LastEpochSnapshot::<Test>::exists());
+
+			// The migration is skipped once the storage is at version 1
+			assert_eq!(
+				MigrateV0ToV1::<Test>::on_runtime_upgrade(),
+				<<Test as frame_system::Config>::DbWeight as Get<RuntimeDbWeight>>::get().reads(1)
+			);
+		});
+	}
+
+	#[test]
+	fn charges_the_partial_exposure_pages() {
+		// Three delegators in pages of two take two pages per validator
+		assert_eq!(max_exposure_pages::<Test>(0), 0);
+		assert_eq!(max_exposure_pages::<Test>(3), 6);
+	}
+}
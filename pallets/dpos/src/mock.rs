@@ -1,17 +1,20 @@
-use crate::{self as pallet_dpos, ReportNewValidatorSet};
+use crate::{self as pallet_dpos, FixedBlockReward, ReportNewValidatorSet, RewardMode};
 use frame_support::{
 	derive_impl, parameter_types,
-	traits::{ConstU128, ConstU16, ConstU32, ConstU64, FindAuthor},
+	traits::{ConstU128, ConstU16, ConstU32, ConstU64, FindAuthor, Hooks},
+	PalletId,
 };
+use frame_system::EnsureRoot;
 use sp_core::H256;
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage,
+	BuildStorage, Perbill,
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
-type Balance = u128;
-type AccountId = u64;
+pub type Balance = u128;
+pub type AccountId = u64;
+pub type BlockNumber = u64;
 
 // Configure a mock runtime to test the pallet. We use the simpler syntax here.
 frame_support::construct_runtime! {
@@ -64,40 +67,252 @@ impl pallet_balances::Config for Test {
 	type MaxLocks = ConstU32<10>;
 	type MaxReserves = ();
 	type ReserveIdentifier = [u8; 8];
-	type RuntimeHoldReason = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type FreezeIdentifier = ();
 	type MaxFreezes = ConstU32<10>;
 }
 
 parameter_types! {
-	pub const MaxValidators: u32 = 10;
+	pub const MaxCandidates: u32 = 10;
+	pub const MaxCandidateDelegators: u32 = 3;
+	pub const MaxValidators: u32 = 3;
+	pub const MinValidators: u32 = 1;
+	pub const MaxDelegateCount: u32 = 2;
+	pub const MinCandidateBond: Balance = 500;
+	pub const MinDelegateAmount: Balance = 100;
+	pub const EpochDuration: BlockNumber = 10;
+	pub const UnbondingEpochs: u32 = 2;
+	pub const MaxCommission: Perbill = Perbill::from_percent(50);
+	pub const CommissionUpdateEpochs: u32 = 1;
+	pub const BlockReward: Balance = 1_000;
+	pub const EpochRewardBudget: Balance = 10_000;
+	pub const TreasuryAccount: AccountId = 100;
+	pub const DposPalletId: PalletId = PalletId(*b"py/dpos_");
+	pub const MaxProfileFieldLength: u32 = 16;
+	pub const ProfileDepositPerByte: Balance = 1;
+	pub const EquivocationSlashFraction: Perbill = Perbill::from_percent(10);
+	pub const UnresponsivenessSlashFraction: Perbill = Perbill::from_percent(1);
+	pub const MaxUnappliedSlashes: u32 = 2;
+	pub const SlashPageSize: u32 = 4;
+	pub const EpochTransitionBlockShare: Perbill = Perbill::from_percent(50);
+	pub const ElectionPageSize: u32 = 4;
+	pub const MaxExposurePageSize: u32 = 2;
 }
 
-pub struct AlwaysSeven;
-impl FindAuthor<AccountId> for AlwaysSeven {
+// The parameters below can be changed by a test through the `ExtBuilder`.
+parameter_types! {
+	pub static BlockAuthor: Option<AccountId> = None;
+	pub static ReportedValidators: Vec<AccountId> = vec![];
+	pub static ActiveRewardMode: RewardMode = RewardMode::PerBlock;
+	pub static TreasuryCut: Perbill = Perbill::zero();
+	pub static SlashDestination: Option<AccountId> = None;
+	pub static SlashDeferEpochs: u32 = 1;
+	pub static ElectionLookahead: BlockNumber = 0;
+}
+
+/// The block author is set by the tests through `BlockAuthor`.
+pub struct MockAuthor;
+impl FindAuthor<AccountId> for MockAuthor {
 	fn find_author<'a, I>(_: I) -> Option<AccountId>
 	where
 		I: 'a + IntoIterator<Item = ([u8; 4], &'a [u8])>,
 	{
-		Some(7)
+		BlockAuthor::get()
 	}
 }
 
-pub struct DoNothing;
-impl ReportNewValidatorSet<AccountId> for DoNothing {
-	fn report_new_validator_set(_: Vec<AccountId>) {}
+/// The last reported validator set is kept in `ReportedValidators`.
+pub struct StoreNewValidatorSet;
+impl ReportNewValidatorSet<AccountId> for StoreNewValidatorSet {
+	fn report_new_validator_set(new_set: Vec<AccountId>) {
+		ReportedValidators::set(new_set);
+	}
 }
 
 impl pallet_dpos::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type NativeBalance = Balances;
 	type MaxValidators = MaxValidators;
-	type FindAuthor = AlwaysSeven;
-	type ReportNewValidatorSet = DoNothing;
+	type MinValidators = MinValidators;
+	type MaxCandidates = MaxCandidates;
+	type MaxCandidateDelegators = MaxCandidateDelegators;
+	type MinDelegateAmount = MinDelegateAmount;
+	type MinCandidateBond = MinCandidateBond;
+	type EpochDuration = EpochDuration;
+	type MaxDelegateCount = MaxDelegateCount;
+	type UnbondingEpochs = UnbondingEpochs;
+	type MaxCommission = MaxCommission;
+	type CommissionUpdateEpochs = CommissionUpdateEpochs;
+	type RewardCurve = FixedBlockReward<BlockReward>;
+	type RewardMode = ActiveRewardMode;
+	type EpochRewardBudget = EpochRewardBudget;
+	type TreasuryCut = TreasuryCut;
+	type TreasuryAccount = TreasuryAccount;
+	type PalletId = DposPalletId;
+	type MaxProfileFieldLength = MaxProfileFieldLength;
+	type ProfileDepositPerByte = ProfileDepositPerByte;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type FindAuthor = MockAuthor;
+	type WeightInfo = ();
+	type ReportNewValidatorSet = StoreNewValidatorSet;
+	type EquivocationSlashFraction = EquivocationSlashFraction;
+	type UnresponsivenessSlashFraction = UnresponsivenessSlashFraction;
+	type SlashDestination = SlashDestination;
+	type SlashDeferEpochs = SlashDeferEpochs;
+	type MaxUnappliedSlashes = MaxUnappliedSlashes;
+	type SlashPageSize = SlashPageSize;
+	type EpochTransitionBlockShare = EpochTransitionBlockShare;
+	type ElectionLookahead = ElectionLookahead;
+	type ElectionPageSize = ElectionPageSize;
+	type MaxExposurePageSize = MaxExposurePageSize;
+}
+
+/// The balance of every account funded by default.
+pub const INITIAL_BALANCE: Balance = 10_000;
+
+/// Builds the genesis state of the mock runtime.
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, Balance)>,
+	genesis_candidates: Vec<(AccountId, Balance)>,
+	genesis_delegations: Vec<(AccountId, AccountId, Balance)>,
+	initial_epoch_index: u32,
+	reward_mode: RewardMode,
+	treasury_cut: Perbill,
+	slash_defer_epochs: u32,
+	election_lookahead: BlockNumber,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: (1..=20).map(|account| (account, INITIAL_BALANCE)).collect(),
+			genesis_candidates: vec![],
+			genesis_delegations: vec![],
+			initial_epoch_index: 0,
+			reward_mode: RewardMode::PerBlock,
+			treasury_cut: Perbill::zero(),
+			slash_defer_epochs: 1,
+			election_lookahead: 0,
+		}
+	}
+}
+
+impl ExtBuilder {
+	/// Fund `account` with `balance` at genesis, on top of the default accounts.
+	pub fn balance(mut self, account: AccountId, balance: Balance) -> Self {
+		self.balances.retain(|(funded, _)| *funded != account);
+		self.balances.push((account, balance));
+		self
+	}
+
+	/// Register the given candidates with their bond at genesis.
+	pub fn genesis_candidates(mut self, genesis_candidates: Vec<(AccountId, Balance)>) -> Self {
+		self.genesis_candidates = genesis_candidates;
+		self
+	}
+
+	/// Delegate the given amounts to the genesis candidates, as (delegator, candidate, amount).
+	pub fn genesis_delegations(
+		mut self,
+		genesis_delegations: Vec<(AccountId, AccountId, Balance)>,
+	) -> Self {
+		self.genesis_delegations = genesis_delegations;
+		self
+	}
+
+	/// Start the chain at the given epoch index.
+	pub fn initial_epoch_index(mut self, initial_epoch_index: u32) -> Self {
+		self.initial_epoch_index = initial_epoch_index;
+		self
+	}
+
+	/// Credit the rewards per block or from the epoch budget.
+	pub fn reward_mode(mut self, reward_mode: RewardMode) -> Self {
+		self.reward_mode = reward_mode;
+		self
+	}
+
+	/// Send a share of the rewards and fees to `TreasuryAccount`.
+	pub fn treasury_cut(mut self, treasury_cut: Perbill) -> Self {
+		self.treasury_cut = treasury_cut;
+		self
+	}
+
+	/// Defer the slashes by the given number of epochs, or apply them right away if zero.
+	pub fn slash_defer_epochs(mut self, slash_defer_epochs: u32) -> Self {
+		self.slash_defer_epochs = slash_defer_epochs;
+		self
+	}
+
+	/// Elect the validator set over the given number of blocks before the epoch boundary.
+	pub fn election_lookahead(mut self, election_lookahead: BlockNumber) -> Self {
+		self.election_lookahead = election_lookahead;
+		self
+	}
+
+	/// Build the genesis storage and move to the first block.
+	pub fn build(self) -> sp_io::TestExternalities {
+		BlockAuthor::set(None);
+		ReportedValidators::set(vec![]);
+		ActiveRewardMode::set(self.reward_mode);
+		TreasuryCut::set(self.treasury_cut);
+		SlashDestination::set(None);
+		SlashDeferEpochs::set(self.slash_defer_epochs);
+		ElectionLookahead::set(self.election_lookahead);
+
+		let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: self.balances }
+			.assimilate_storage(&mut storage)
+			.unwrap();
+		pallet_dpos::GenesisConfig::<Test> {
+			genesis_candidates: self.genesis_candidates,
+			genesis_delegations: self.genesis_delegations,
+			initial_epoch_index: self.initial_epoch_index,
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(storage);
+		// Go past genesis block so events get deposited
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// Build the genesis storage, run `test` against it and check the storage invariants.
+	pub fn build_and_execute(self, test: impl FnOnce()) {
+		self.build().execute_with(|| {
+			test();
+			Dpos::do_try_state().expect("The storage invariants must hold after the test");
+		});
+	}
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	// learn how to improve your test setup:
-	// https://paritytech.github.io/polkadot-sdk/master/polkadot_sdk_docs/guides/your_first_pallet/index.html
-	frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into()
-}
\ No newline at end of file
+	ExtBuilder::default().build()
+}
+
+/// Run the `on_initialize` hook of the pallet for every block up to `n`.
+pub fn run_to_block(n: BlockNumber) {
+	while System::block_number() < n {
+		let block_number = System::block_number() + 1;
+		System::set_block_number(block_number);
+		Dpos::on_initialize(block_number);
+	}
+}
+
+/// Run the blocks up to the first block of the epoch `epoch_index`.
+pub fn run_to_epoch(epoch_index: u32) {
+	run_to_block(BlockNumber::from(epoch_index) * EpochDuration::get());
+}
+
+/// The events deposited by the pallet so far.
+pub fn dpos_events() -> Vec<pallet_dpos::Event<Test>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::Dpos(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
@@ -5,12 +5,22 @@ use frame::deps::frame_support::{
 
 use codec::{Decode, Encode, MaxEncodedLen};
 
-use sp_runtime::traits::Zero;
+use sp_runtime::{traits::Zero, Perbill};
 use scale_info::TypeInfo;
 use sp_core::RuntimeDebug;
 use crate::{BalanceOf, Config};
 use super::DispatchResultWithValue;
 
+/// The `CandidateStatus` enum represents the lifecycle state of a candidate in the DPoS system.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq, Clone, Copy)]
+pub enum CandidateStatus {
+	/// The candidate can be selected as a validator and accepts new delegations.
+	Active,
+	/// The candidate requested to leave the pool. The exit can be executed once the epoch index
+	/// reaches the stored value.
+	Leaving(u32),
+}
+
 /// The `Candidate` struct represents a candidate in the DPoS system.
 /// It includes the candidate's bond and the sum of delegated amounts.
 #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq)]
@@ -20,22 +30,34 @@ pub struct Candidate<T: Config> {
     pub bond: BalanceOf<T>,
 	/// The total amount delegated to the candidate.
     pub sum_delegation: BalanceOf<T>,
+	/// The lifecycle status of the candidate.
+    pub status: CandidateStatus,
+	/// The share of each block reward the candidate takes before splitting it with its
+	/// delegators.
+    pub commission: Perbill,
+	/// The epoch index in which the commission was last set.
+    pub commission_updated_at: u32,
 }
 
 impl<T: Config> Candidate<T> {
-	/// Creates a new candidate with the given bond.
+	/// Creates a new candidate with the given bond and commission.
     ///
     /// # Arguments
     ///
     /// * `bond` - The bond amount staked by the candidate.
+    /// * `commission` - The share of each block reward taken by the candidate.
+    /// * `epoch_index` - The epoch index in which the candidate registers.
     ///
     /// # Returns
     ///
     /// A new `Candidate` instance.
-    pub fn new(bond: BalanceOf<T>) -> Self {
+    pub fn new(bond: BalanceOf<T>, commission: Perbill, epoch_index: u32) -> Self {
         Self {
             bond,
             sum_delegation: Zero::zero(),
+            status: CandidateStatus::Active,
+            commission,
+            commission_updated_at: epoch_index,
         }
     }
 
@@ -90,6 +112,31 @@ impl<T: Config> Candidate<T> {
 		self.bond = bond;
 	}
 
+	/// Updates the candidate's commission.
+    ///
+    /// # Arguments
+    ///
+    /// * `commission` - The new share of each block reward taken by the candidate.
+    /// * `epoch_index` - The epoch index in which the commission is updated.
+	pub fn set_commission(&mut self, commission: Perbill, epoch_index: u32) {
+		self.commission = commission;
+		self.commission_updated_at = epoch_index;
+	}
+
+	/// Returns `true` if the candidate can be selected as a validator and accepts delegations.
+	pub fn is_active(&self) -> bool {
+		self.status == CandidateStatus::Active
+	}
+
+	/// Marks the candidate as leaving the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `exit_epoch` - The epoch index from which the exit can be executed.
+	pub fn leave(&mut self, exit_epoch: u32) {
+		self.status = CandidateStatus::Leaving(exit_epoch);
+	}
+
 	/// Returns the total amount staked by the candidate, including both the bond and the delegated sum.
     ///
     /// # Returns
@@ -100,6 +147,39 @@ impl<T: Config> Candidate<T> {
 	}
 }
 
+/// The `BondLessRequest` struct represents a decrease of a candidate's bond waiting for its
+/// unbonding period. The amount is no longer part of the bond but stays on hold until
+/// `unlock_epoch`.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq)]
+#[scale_info(skip_type_params(T))]
+pub struct BondLessRequest<T: Config> {
+	/// The amount of tokens waiting to be released.
+    pub amount: BalanceOf<T>,
+	/// The epoch index from which the amount can be released.
+    pub unlock_epoch: u32,
+}
+
+impl<T: Config> BondLessRequest<T> {
+	/// Creates a new bond decrease request.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount of tokens waiting to be released.
+    /// * `unlock_epoch` - The epoch index from which the amount can be released.
+    ///
+    /// # Returns
+    ///
+    /// A new `BondLessRequest` instance.
+    pub fn new(amount: BalanceOf<T>, unlock_epoch: u32) -> Self {
+        Self { amount, unlock_epoch }
+    }
+
+	/// Returns `true` if the request can be executed at the given epoch index.
+    pub fn is_unlocked(&self, epoch_index: u32) -> bool {
+        epoch_index >= self.unlock_epoch
+    }
+}
+
 /// A type alias for a set of candidates, represented as a vector of tuples containing the candidate's account ID and bond amount.
 #[allow(type_alias_bounds)]
 pub type CandidateSet<T: Config> = sp_std::vec::Vec<(T::AccountId, BalanceOf<T>)>;
\ No newline at end of file
@@ -2,6 +2,7 @@ use crate::{BalanceOf, Config};
 use codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
 use sp_core::RuntimeDebug;
+use sp_runtime::{traits::Zero, Percent};
 
 
 /// The `Delegation` struct represents a delegation in the DPoS system.
@@ -11,6 +12,9 @@ use sp_core::RuntimeDebug;
 pub struct Delegation<T: Config> {
     /// The amount of tokens delegated.
     pub amount: BalanceOf<T>,
+    /// The share of the rewards earned through this delegation that is delegated again
+    /// automatically.
+    pub auto_compound: Percent,
 }
 
 impl<T: Config> Delegation<T> {
@@ -24,7 +28,7 @@ impl<T: Config> Delegation<T> {
     ///
     /// A new `Delegation` instance.
     pub fn new(amount: BalanceOf<T>) -> Self {
-        Self { amount }
+        Self { amount, auto_compound: Zero::zero() }
     }
     
     /// Sets the amount of tokens delegated.
@@ -35,4 +39,52 @@ impl<T: Config> Delegation<T> {
     pub fn set_amount(&mut self, amount: BalanceOf<T>) {
         self.amount = amount;
     }
-}
\ No newline at end of file
+
+    /// Sets the share of the rewards that is delegated again automatically.
+    ///
+    /// # Arguments
+    ///
+    /// * `auto_compound` - The new share of the rewards to compound.
+    pub fn set_auto_compound(&mut self, auto_compound: Percent) {
+        self.auto_compound = auto_compound;
+    }
+}
+
+/// The `UnbondingRequest` struct represents an undelegation waiting for its unbonding period.
+/// The amount is no longer part of the delegation but stays on hold until `unlock_epoch`.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq)]
+#[scale_info(skip_type_params(T))]
+pub struct UnbondingRequest<T: Config> {
+    /// The candidate the amount was undelegated from.
+    pub candidate: T::AccountId,
+    /// The amount of tokens waiting to be released.
+    pub amount: BalanceOf<T>,
+    /// The epoch index from which the amount can be released.
+    pub unlock_epoch: u32,
+}
+
+impl<T: Config> UnbondingRequest<T> {
+    /// Creates a new unbonding request.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidate` - The candidate the amount was undelegated from.
+    /// * `amount` - The amount of tokens waiting to be released.
+    /// * `unlock_epoch` - The epoch index from which the amount can be released.
+    ///
+    /// # Returns
+    ///
+    /// A new `UnbondingRequest` instance.
+    pub fn new(candidate: T::AccountId, amount: BalanceOf<T>, unlock_epoch: u32) -> Self {
+        Self { candidate, amount, unlock_epoch }
+    }
+
+    /// Returns `true` if the request can be executed at the given epoch index.
+    pub fn is_unlocked(&self, epoch_index: u32) -> bool {
+        epoch_index >= self.unlock_epoch
+    }
+}
+
+/// A type alias for a set of delegations, represented as a vector of tuples containing the delegator's account ID, the candidate's account ID and the delegated amount.
+#[allow(type_alias_bounds)]
+pub type DelegationSet<T: Config> = sp_std::vec::Vec<(T::AccountId, T::AccountId, BalanceOf<T>)>;
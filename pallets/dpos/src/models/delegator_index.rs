@@ -0,0 +1,139 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use core::cmp::Ordering;
+use frame_support::{BoundedVec, DefaultNoBound};
+use scale_info::TypeInfo;
+use sp_core::RuntimeDebug;
+use sp_std::vec::Vec;
+use crate::{BalanceOf, Config};
+
+/// The `DelegatorIndex` struct represents the delegators of a candidate.
+/// Delegators are kept ordered from the highest to the lowest delegated amount, delegators with
+/// the same amount being ordered by account ID. The total order lets every lookup use a binary
+/// search, so the delegated amount must be known to find a delegator.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq, DefaultNoBound)]
+#[scale_info(skip_type_params(T))]
+pub struct DelegatorIndex<T: Config>(
+	BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxCandidateDelegators>,
+);
+
+impl<T: Config> DelegatorIndex<T> {
+	/// Compares an entry of the index with the position of a delegator and its delegated amount.
+	fn compare(
+		entry: &(T::AccountId, BalanceOf<T>),
+		delegator: &T::AccountId,
+		amount: &BalanceOf<T>,
+	) -> Ordering {
+		let (entry_delegator, entry_amount) = entry;
+		amount.cmp(entry_amount).then_with(|| entry_delegator.cmp(delegator))
+	}
+
+	/// Finds the position of a delegator in the index.
+	///
+	/// # Arguments
+	///
+	/// * `delegator` - The delegator to look up.
+	/// * `amount` - The amount currently delegated by the delegator.
+	///
+	/// # Returns
+	///
+	/// `Ok` with the position of the delegator, or `Err` with the position it would be inserted
+	/// at.
+	fn search(&self, delegator: &T::AccountId, amount: &BalanceOf<T>) -> Result<usize, usize> {
+		self.0.binary_search_by(|entry| Self::compare(entry, delegator, amount))
+	}
+
+	/// Inserts a delegator at its position in the index.
+	///
+	/// # Arguments
+	///
+	/// * `delegator` - The delegator to insert.
+	/// * `amount` - The amount delegated by the delegator.
+	///
+	/// # Returns
+	///
+	/// `false` if the index is full or already contains the delegator with this amount.
+	pub fn insert(&mut self, delegator: T::AccountId, amount: BalanceOf<T>) -> bool {
+		match self.search(&delegator, &amount) {
+			Ok(_) => false,
+			Err(index) => self.0.try_insert(index, (delegator, amount)).is_ok(),
+		}
+	}
+
+	/// Removes a delegator from the index.
+	///
+	/// # Arguments
+	///
+	/// * `delegator` - The delegator to remove.
+	/// * `amount` - The amount currently delegated by the delegator.
+	///
+	/// # Returns
+	///
+	/// `false` if the delegator is not in the index with this amount.
+	pub fn remove(&mut self, delegator: &T::AccountId, amount: &BalanceOf<T>) -> bool {
+		match self.search(delegator, amount) {
+			Ok(index) => {
+				self.0.remove(index);
+				true
+			},
+			Err(_) => false,
+		}
+	}
+
+	/// Moves a delegator to its new position after its delegated amount changed.
+	///
+	/// # Arguments
+	///
+	/// * `delegator` - The delegator to move.
+	/// * `old_amount` - The amount delegated before the change.
+	/// * `new_amount` - The amount delegated after the change.
+	///
+	/// # Returns
+	///
+	/// `false` if the delegator is not in the index with `old_amount`.
+	pub fn update(
+		&mut self,
+		delegator: &T::AccountId,
+		old_amount: &BalanceOf<T>,
+		new_amount: BalanceOf<T>,
+	) -> bool {
+		// The removal frees the slot used by the insertion
+		self.remove(delegator, old_amount) && self.insert(delegator.clone(), new_amount)
+	}
+
+	/// Returns `true` if the index contains the delegator with the given amount.
+	pub fn contains(&self, delegator: &T::AccountId, amount: &BalanceOf<T>) -> bool {
+		self.search(delegator, amount).is_ok()
+	}
+
+	/// Returns the delegator with the lowest delegated amount.
+	pub fn lowest(&self) -> Option<&(T::AccountId, BalanceOf<T>)> {
+		self.0.last()
+	}
+
+	/// Returns `true` if the index holds `MaxCandidateDelegators` delegators.
+	pub fn is_full(&self) -> bool {
+		self.0.is_full()
+	}
+
+	/// Returns the number of delegators in the index.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Returns `true` if the index has no delegator.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Returns an iterator over the delegators and their delegated amounts, from the highest to
+	/// the lowest delegation.
+	pub fn iter(&self) -> impl Iterator<Item = &(T::AccountId, BalanceOf<T>)> {
+		self.0.iter()
+	}
+
+	/// Consumes the index and returns the delegators and their delegated amounts, from the
+	/// highest to the lowest delegation.
+	pub fn into_inner(self) -> Vec<(T::AccountId, BalanceOf<T>)> {
+		self.0.into_inner()
+	}
+}
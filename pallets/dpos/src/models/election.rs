@@ -0,0 +1,27 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_core::RuntimeDebug;
+
+/// The `ElectionPhase` enum represents the progress of the multi-block election of the next
+/// validator set. Each phase stores the cursor from which the next page resumes.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq, Clone, Default)]
+pub enum ElectionPhase<AccountId> {
+	/// No election is running.
+	#[default]
+	Idle,
+	/// The eligible candidates are being collected from the candidate pool, resuming after the
+	/// last collected candidate if any.
+	Candidates { last: Option<AccountId> },
+	/// The exposures of the elected validators are being captured for the next epoch, resuming
+	/// at the validator at position `validator`.
+	Exposures { validator: u32 },
+	/// The next validator set and its exposures are complete and wait for the epoch boundary.
+	Ready,
+}
+
+impl<AccountId> ElectionPhase<AccountId> {
+	/// Whether a page has to be processed in this phase.
+	pub fn is_running(&self) -> bool {
+		matches!(self, Self::Candidates { .. } | Self::Exposures { .. })
+	}
+}
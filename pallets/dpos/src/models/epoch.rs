@@ -1,53 +1,41 @@
-use sp_std::collections::btree_map::BTreeMap;
 use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::BoundedVec;
 use scale_info::TypeInfo;
 use sp_core::RuntimeDebug;
+use sp_runtime::Perbill;
 use crate::{BalanceOf, Config};
 
-/// The `Epoch` struct represents an epoch in the DPoS system.
-/// It includes the validators and delegations for the epoch.
-#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq)]
+/// The `ExposureOverview` struct represents a validator as captured at the start of an epoch.
+/// The delegators backing the validator are stored separately in pages of `ExposurePage`.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq, Clone)]
 #[scale_info(skip_type_params(T))]
-pub struct Epoch<T: Config> {
-    /// A map of validators and their staked amounts.
-	pub validators: BTreeMap<T::AccountId, BalanceOf<T>>,
- 	/// A map of delegations, represented as tuples of delegator and candidate account IDs, and their delegated amounts.
-	pub delegations: BTreeMap<(T::AccountId, T::AccountId), BalanceOf<T>>,
+pub struct ExposureOverview<T: Config> {
+	/// The bond amount staked by the validator.
+	pub bond: BalanceOf<T>,
+	/// The commission the validator takes from each block reward.
+	pub commission: Perbill,
+	/// The bond of the validator and the delegated amounts of all its pages.
+	pub total: BalanceOf<T>,
+	/// The number of delegators over all the pages.
+	pub delegator_count: u32,
+	/// The number of pages the delegators are split into.
+	pub page_count: u32,
 }
 
-impl<T: Config> Epoch<T> {
-    /// Creates a new epoch with default values.
-    ///
-    /// # Returns
-    ///
-    /// A new `Epoch` instance with empty validators and delegations.
-	pub fn default() -> Self {
-		Self { validators: BTreeMap::default(), delegations: BTreeMap::default() }
-	}
-
-    /// Adds a delegator to the epoch.
-    ///
-    /// # Arguments
-    ///
-    /// * `delegator` - The account ID of the delegator.
-    /// * `candidate` - The account ID of the candidate.
-    /// * `amount` - The amount of tokens delegated.
-	pub fn add_delegator(
-		self: &mut Self,
-		delegator: T::AccountId,
-		candidate: T::AccountId,
-		amount: BalanceOf<T>,
-	) {
-		self.delegations.insert((delegator, candidate), amount);
+impl<T: Config> ExposureOverview<T> {
+	/// Creates the overview of a validator without any delegator.
+	///
+	/// # Arguments
+	///
+	/// * `bond` - The amount of tokens staked by the validator.
+	/// * `commission` - The commission the validator takes from each block reward.
+	pub fn new(bond: BalanceOf<T>, commission: Perbill) -> Self {
+		Self { bond, commission, total: bond, delegator_count: 0, page_count: 0 }
 	}
+}
 
-    /// Adds a validator to the epoch.
-    ///
-    /// # Arguments
-    ///
-    /// * `candidate` - The account ID of the candidate.
-    /// * `amount` - The amount of tokens staked by the validator.
-	pub fn add_validator(self: &mut Self, candidate: T::AccountId, amount: BalanceOf<T>) {
-		self.validators.insert(candidate, amount);
-	}
-}
\ No newline at end of file
+/// A page of the delegators backing a validator in an epoch, with their delegated amounts.
+pub type ExposurePage<T> = BoundedVec<
+	(<T as frame_system::Config>::AccountId, BalanceOf<T>),
+	<T as Config>::MaxExposurePageSize,
+>;
@@ -2,6 +2,16 @@ pub mod candidate;
 pub use candidate::*;
 pub mod delegate;
 pub use delegate::*;
+pub mod delegator_index;
+pub use delegator_index::*;
+pub mod election;
+pub use election::*;
 pub mod epoch;
 pub use epoch::*;
-pub type DispatchResultWithValue<T> = Result<T, sp_runtime::DispatchError>;
\ No newline at end of file
+pub mod profile;
+pub use profile::*;
+pub mod query;
+pub use query::*;
+pub mod slash;
+pub use slash::*;
+pub type DispatchResultWithValue<T> = Result<T, sp_runtime::DispatchError>;
@@ -0,0 +1,52 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::BoundedVec;
+use scale_info::TypeInfo;
+use sp_core::RuntimeDebug;
+use crate::Config;
+
+/// A field of a candidate profile, bounded by `MaxProfileFieldLength` bytes.
+pub type ProfileField<T> = BoundedVec<u8, <T as Config>::MaxProfileFieldLength>;
+
+/// The `CandidateProfile` struct represents the public information of a candidate.
+/// It lets delegators identify the candidates through a readable name and contact details.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq, Clone)]
+#[scale_info(skip_type_params(T))]
+pub struct CandidateProfile<T: Config> {
+	/// The name under which the candidate is displayed.
+	pub display_name: ProfileField<T>,
+	/// The website of the candidate.
+	pub website: ProfileField<T>,
+	/// A way to contact the candidate, such as an email address or a chat handle.
+	pub contact: ProfileField<T>,
+	/// The p2p address of the candidate's node.
+	pub p2p_address: ProfileField<T>,
+}
+
+impl<T: Config> CandidateProfile<T> {
+	/// Creates a new candidate profile.
+	///
+	/// # Arguments
+	///
+	/// * `display_name` - The name under which the candidate is displayed.
+	/// * `website` - The website of the candidate.
+	/// * `contact` - A way to contact the candidate.
+	/// * `p2p_address` - The p2p address of the candidate's node.
+	///
+	/// # Returns
+	///
+	/// A new `CandidateProfile` instance.
+	pub fn new(
+		display_name: ProfileField<T>,
+		website: ProfileField<T>,
+		contact: ProfileField<T>,
+		p2p_address: ProfileField<T>,
+	) -> Self {
+		Self { display_name, website, contact, p2p_address }
+	}
+
+	/// Returns the number of bytes the profile takes in storage, which the profile deposit is
+	/// proportional to.
+	pub fn byte_len(&self) -> u32 {
+		self.encoded_size() as u32
+	}
+}
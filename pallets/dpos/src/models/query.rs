@@ -0,0 +1,5 @@
+//! The summary types returned by the queries of the pallet. They are defined in the runtime API
+//! crate, so that clients can decode them without depending on the pallet.
+pub use pallet_dpos_runtime_api::{
+	CandidateSummary, DelegationRewardEstimate, DelegationSummary, ValidatorSetSummary,
+};
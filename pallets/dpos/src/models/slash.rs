@@ -0,0 +1,32 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::BoundedVec;
+use scale_info::TypeInfo;
+use sp_core::RuntimeDebug;
+use sp_runtime::Perbill;
+use crate::{BalanceOf, Config};
+
+/// The `OffenceKind` enum represents the kinds of misbehaviour a validator can be slashed for.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq, Clone, Copy)]
+pub enum OffenceKind {
+	/// The validator produced or signed conflicting blocks for the same slot.
+	Equivocation,
+	/// The validator did not take part in block production while being in the active set.
+	Unresponsiveness,
+}
+
+/// The `UnappliedSlash` struct represents a slash computed from the epoch exposures.
+/// It includes the amount taken from the validator bond and from each of its delegators.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq)]
+#[scale_info(skip_type_params(T))]
+pub struct UnappliedSlash<T: Config> {
+	/// The validator being slashed.
+	pub validator: T::AccountId,
+	/// The offence the validator is slashed for.
+	pub kind: OffenceKind,
+	/// The fraction of the exposed stake being slashed.
+	pub slash_fraction: Perbill,
+	/// The amount slashed from the validator bond.
+	pub own: BalanceOf<T>,
+	/// The amounts slashed from the delegators of the validator.
+	pub others: BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxCandidateDelegators>,
+}
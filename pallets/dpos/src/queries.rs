@@ -0,0 +1,194 @@
+//! Read-only queries backing the `DposApi` runtime API.
+//!
+//! The queries collect the staking state into the summary types of [`crate::models`] so that
+//! clients don't have to decode the pallet storage themselves.
+use crate::{
+	models::*,
+	pallet::*,
+	reward_curve::{RewardCurve, RewardMode},
+};
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{
+		traits::{Saturating, Zero},
+		Perbill, SaturatedConversion,
+	},
+};
+use sp_std::vec::Vec;
+
+impl<T: Config> Pallet<T> {
+	/// The candidates of the candidate pool with their stake, ordered by account ID rather than by
+	/// the hashed keys of the pool.
+	pub fn candidates() -> Vec<CandidateSummary<T::AccountId, BalanceOf<T>>> {
+		let mut candidates: Vec<_> = CandidatePool::<T>::iter()
+			.map(|(candidate_id, candidate)| CandidateSummary {
+				delegator_count: CandidateDelegators::<T>::get(&candidate_id).len() as u32,
+				candidate_id,
+				bond: candidate.bond,
+				total_delegations: candidate.sum_delegation,
+				total_stake: candidate.total(),
+				commission: candidate.commission,
+				is_active: candidate.is_active(),
+			})
+			.collect();
+		candidates.sort_by(|a, b| a.candidate_id.cmp(&b.candidate_id));
+		candidates
+	}
+
+	/// The delegations of a delegator, including the amounts waiting for their unbonding
+	/// period.
+	pub fn delegations(
+		delegator: &T::AccountId,
+	) -> Vec<DelegationSummary<T::AccountId, BalanceOf<T>>> {
+		let mut delegations: Vec<_> = DelegationInfos::<T>::iter_prefix(delegator)
+			.map(|(candidate_id, delegation)| DelegationSummary {
+				candidate_id,
+				amount: delegation.amount,
+				auto_compound: delegation.auto_compound,
+				unbonding_amount: Zero::zero(),
+				unlock_epoch: None,
+			})
+			.collect();
+
+		for request in UnbondingDelegations::<T>::get(delegator) {
+			match delegations.iter_mut().find(|summary| summary.candidate_id == request.candidate) {
+				Some(summary) => {
+					summary.unbonding_amount =
+						summary.unbonding_amount.saturating_add(request.amount);
+					summary.unlock_epoch = Some(request.unlock_epoch);
+				},
+				// The whole delegation is being undelegated
+				None => delegations.push(DelegationSummary {
+					candidate_id: request.candidate,
+					amount: Zero::zero(),
+					auto_compound: Default::default(),
+					unbonding_amount: request.amount,
+					unlock_epoch: Some(request.unlock_epoch),
+				}),
+			}
+		}
+		delegations
+	}
+
+	/// The rewards of an account that can be claimed.
+	pub fn pending_rewards(account: &T::AccountId) -> BalanceOf<T> {
+		Rewards::<T>::get(account)
+	}
+
+	/// The validator set of the current epoch.
+	pub fn current_validator_set() -> ValidatorSetSummary<T::AccountId, BalanceOf<T>> {
+		ValidatorSetSummary {
+			epoch_index: EpochIndex::<T>::get(),
+			validators: CurrentValidators::<T>::get().into_inner(),
+		}
+	}
+
+	/// The validator set that would be selected if the epoch ended now.
+	pub fn next_validator_set() -> ValidatorSetSummary<T::AccountId, BalanceOf<T>> {
+		ValidatorSetSummary {
+			epoch_index: EpochIndex::<T>::get().saturating_add(1),
+			validators: Self::select_validator_set(),
+		}
+	}
+
+	/// Simulate a delegation of `amount` from `delegator` to `candidate` and estimate its outcome
+	/// if the epoch ended right after it.
+	///
+	/// The validator selection is re-run with the stake of the candidate increased by `amount`,
+	/// less the lowest delegation that is kicked when the candidate is full. The epoch reward
+	/// assumes that the validators author the same number of blocks and applies the
+	/// `TreasuryCut`, the commission of the candidate and the pro rata split of
+	/// `distribute_block_reward` to the whole delegation of `delegator` to `candidate`.
+	///
+	/// Fails with the error `delegate` would raise if the delegation can't be made.
+	pub fn estimate_delegation_reward(
+		delegator: &T::AccountId,
+		candidate: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> DispatchResultWithValue<DelegationRewardEstimate<BalanceOf<T>>> {
+		ensure!(!amount.is_zero(), Error::<T>::InvalidZeroAmount);
+		let candidate_detail = Self::get_candidate(candidate)?;
+		ensure!(candidate_detail.is_active(), Error::<T>::CandidateIsLeaving);
+
+		let delegation = DelegationInfos::<T>::get(delegator, candidate);
+		let mut kicked_amount: BalanceOf<T> = Zero::zero();
+		if delegation.is_none() {
+			ensure!(
+				DelegateCountMap::<T>::get(delegator) < T::MaxDelegateCount::get(),
+				Error::<T>::TooManyCandidateDelegations
+			);
+			let candidate_delegators = CandidateDelegators::<T>::get(candidate);
+			if candidate_delegators.is_full() {
+				let lowest_amount = candidate_delegators
+					.lowest()
+					.map(|(_, lowest_amount)| *lowest_amount)
+					.ok_or(Error::<T>::TooManyDelegatorsInPool)?;
+				ensure!(amount > lowest_amount, Error::<T>::TooManyDelegatorsInPool);
+				kicked_amount = lowest_amount;
+			}
+		}
+		let delegated_amount = delegation
+			.map(|delegation| delegation.amount)
+			.unwrap_or_else(Zero::zero)
+			.saturating_add(amount);
+		Self::check_delegated_amount(delegated_amount)?;
+
+		// Re-run the validator selection with the hypothetical stake
+		let added_stake = amount.saturating_sub(kicked_amount);
+		let mut candidates = Self::eligible_candidates();
+		for (candidate_id, _, total_stake) in candidates.iter_mut() {
+			if candidate_id == candidate {
+				*total_stake = total_stake.saturating_add(added_stake);
+			}
+		}
+		Self::rank_candidates(&mut candidates);
+		let position = candidates
+			.iter()
+			.position(|(candidate_id, _, _)| candidate_id == candidate)
+			.ok_or(Error::<T>::CandidateDoesNotExist)?;
+		let rank = position.saturating_add(1) as u32;
+		let is_active_validator = candidates.len() as u32 >= T::MinValidators::get() &&
+			rank <= T::MaxValidators::get();
+
+		let total_stake = candidate_detail.total().saturating_add(added_stake);
+		let epoch_reward = if is_active_validator {
+			let validator_count = (candidates.len() as u32).min(T::MaxValidators::get());
+			let validator_reward =
+				Self::estimate_validator_epoch_reward(total_stake, validator_count);
+			let validator_reward =
+				validator_reward.saturating_sub(T::TreasuryCut::get() * validator_reward);
+			let stakers_reward =
+				validator_reward.saturating_sub(candidate_detail.commission * validator_reward);
+			Perbill::from_rational(delegated_amount, total_stake) * stakers_reward
+		} else {
+			Zero::zero()
+		};
+
+		Ok(DelegationRewardEstimate {
+			is_active_validator,
+			rank,
+			total_stake,
+			delegated_amount,
+			epoch_reward,
+		})
+	}
+
+	/// The reward of a validator backed by `total_stake` for one epoch, assuming the
+	/// `validator_count` validators author the same number of blocks.
+	fn estimate_validator_epoch_reward(
+		total_stake: BalanceOf<T>,
+		validator_count: u32,
+	) -> BalanceOf<T> {
+		if validator_count.is_zero() {
+			return Zero::zero();
+		}
+		match T::RewardMode::get() {
+			RewardMode::PerBlock => {
+				let blocks = T::EpochDuration::get().saturated_into::<u32>() / validator_count;
+				T::RewardCurve::block_reward(total_stake).saturating_mul(blocks.into())
+			},
+			RewardMode::EpochBudget =>
+				T::EpochRewardBudget::get() / BalanceOf::<T>::from(validator_count),
+		}
+	}
+}
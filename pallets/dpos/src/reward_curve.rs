@@ -0,0 +1,125 @@
+//! Reward curves deciding how much is minted for each authored block.
+//!
+//! The runtime picks one of the curves below through [`Config::RewardCurve`]. The block reward is
+//! then split between the block author and its delegators, see
+//! [`Pallet::distribute_block_reward`].
+//!
+//! With [`RewardMode::EpochBudget`], the reward curve is not used. A fixed
+//! [`Config::EpochRewardBudget`] is instead split at the end of each epoch between the validators
+//! in proportion to the blocks they authored.
+use crate::pallet::*;
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{
+		traits::{Saturating, Zero},
+		PerThing, Perbill, SaturatedConversion,
+	},
+	traits::fungible::Inspect,
+};
+use sp_core::RuntimeDebug;
+use sp_std::marker::PhantomData;
+
+/// The way block rewards are credited to the validators and their delegators.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, PartialEq, Eq, Clone, Copy)]
+pub enum RewardMode {
+	/// Every block mints the reward decided by [`Config::RewardCurve`] for its author.
+	PerBlock,
+	/// The authored blocks are counted during the epoch and [`Config::EpochRewardBudget`] is
+	/// split between the validators at the epoch boundary.
+	EpochBudget,
+}
+
+/// Decide the reward minted for a block.
+pub trait RewardCurve<T: Config> {
+	/// The reward minted for a block authored by a validator backed by `validator_stake`.
+	fn block_reward(validator_stake: BalanceOf<T>) -> BalanceOf<T>;
+}
+
+/// Mint a fixed share of the stake backing the block author on every block.
+pub struct StakePercentage<P>(PhantomData<P>);
+
+impl<T: Config, P: Get<Perbill>> RewardCurve<T> for StakePercentage<P> {
+	fn block_reward(validator_stake: BalanceOf<T>) -> BalanceOf<T> {
+		P::get() * validator_stake
+	}
+}
+
+/// Mint a fixed amount on every block, regardless of the stake of the block author.
+pub struct FixedBlockReward<R>(PhantomData<R>);
+
+impl<T: Config, R: Get<BalanceOf<T>>> RewardCurve<T> for FixedBlockReward<R> {
+	fn block_reward(_validator_stake: BalanceOf<T>) -> BalanceOf<T> {
+		R::get()
+	}
+}
+
+/// Target an annual inflation of the total issuance, spread evenly over the blocks of
+/// `EpochsPerYear` epochs of `EpochDuration` blocks.
+pub struct AnnualInflation<I, E>(PhantomData<(I, E)>);
+
+impl<T: Config, I: Get<Perbill>, E: Get<u32>> RewardCurve<T> for AnnualInflation<I, E> {
+	fn block_reward(_validator_stake: BalanceOf<T>) -> BalanceOf<T> {
+		Pallet::<T>::inflation_per_block(I::get(), E::get())
+	}
+}
+
+/// Adjust the annual inflation to the share of the total issuance that is staked, similar to the
+/// NPoS reward curve.
+///
+/// The inflation grows linearly from `MinInflation` when nothing is staked up to `MaxInflation`
+/// at `IdealStake`, then decreases linearly back to `MinInflation` when everything is staked. The
+/// staked amount is the total stake backing the current validator set.
+pub struct IdealStakingCurve<MinInflation, MaxInflation, IdealStake, EpochsPerYear>(
+	PhantomData<(MinInflation, MaxInflation, IdealStake, EpochsPerYear)>,
+);
+
+impl<T, MinInflation, MaxInflation, IdealStake, EpochsPerYear> RewardCurve<T>
+	for IdealStakingCurve<MinInflation, MaxInflation, IdealStake, EpochsPerYear>
+where
+	T: Config,
+	MinInflation: Get<Perbill>,
+	MaxInflation: Get<Perbill>,
+	IdealStake: Get<Perbill>,
+	EpochsPerYear: Get<u32>,
+{
+	fn block_reward(_validator_stake: BalanceOf<T>) -> BalanceOf<T> {
+		let min_inflation = MinInflation::get();
+		let inflation_range = MaxInflation::get().saturating_sub(min_inflation);
+		let ideal_stake = IdealStake::get();
+
+		let total_issuance = T::NativeBalance::total_issuance();
+		let total_staked = CurrentValidators::<T>::get()
+			.iter()
+			.fold(BalanceOf::<T>::zero(), |total, (_, _, stake)| total.saturating_add(*stake));
+		let staked = Perbill::from_rational(total_staked, total_issuance);
+
+		let progress = if staked <= ideal_stake {
+			Perbill::from_rational(staked.deconstruct(), ideal_stake.deconstruct())
+		} else {
+			Perbill::from_rational(
+				staked.left_from_one().deconstruct(),
+				ideal_stake.left_from_one().deconstruct(),
+			)
+		};
+		let inflation = min_inflation.saturating_add(progress * inflation_range);
+
+		Pallet::<T>::inflation_per_block(inflation, EpochsPerYear::get())
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The share of an annual inflation of the total issuance minted in a single block.
+	pub(crate) fn inflation_per_block(
+		annual_inflation: Perbill,
+		epochs_per_year: u32,
+	) -> BalanceOf<T> {
+		let blocks_per_year = T::EpochDuration::get()
+			.saturated_into::<u32>()
+			.saturating_mul(epochs_per_year);
+		if blocks_per_year.is_zero() {
+			return Zero::zero();
+		}
+		let annual_reward = annual_inflation * T::NativeBalance::total_issuance();
+		annual_reward / BalanceOf::<T>::from(blocks_per_year)
+	}
+}
@@ -0,0 +1,279 @@
+//! Slashing of misbehaving validators.
+//!
+//! Offences are reported to the pallet through [`ReportOffence`]. The slash is computed from the
+//! [`EpochExposures`] of the running epoch so that the validator and its delegators are penalised
+//! for the stake they had backing the active set when the offence happened. The slashed amount is
+//! taken from the
+//! `CandidateBondReserved` and `DelegateAmountReserved` holds and is either burnt or moved to
+//! [`Config::SlashDestination`].
+//!
+//! Unless [`Config::SlashDeferEpochs`] is zero, a computed slash is stored in
+//! [`UnappliedSlashes`] under the epoch it was reported in and only applied
+//! `SlashDeferEpochs` epochs later, which leaves governance time to cancel it.
+//!
+//! The due slashes are applied by `on_initialize` in pages of at most [`Config::SlashPageSize`]
+//! items, where an item is a slashed validator or one of its delegators, starting from the epoch
+//! stored in [`NextSlashEpoch`]. All the delegators of a slash are slashed within the same block.
+//! The slashes of an epoch are applied from the last one, so that the indices of the remaining
+//! slashes stay valid for `cancel_deferred_slash`.
+use crate::{models::*, pallet::*};
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{
+		traits::{Saturating, Zero},
+		Perbill,
+	},
+	traits::{
+		fungible::MutateHold,
+		tokens::{Fortitude, Precision, Restriction},
+	},
+};
+
+impl<T: Config> ReportOffence<T::AccountId> for Pallet<T> {
+	fn report_offence(offender: T::AccountId, kind: OffenceKind) -> DispatchResult {
+		let epoch_index = EpochIndex::<T>::get();
+		ensure!(
+			!OffenceReports::<T>::contains_key(epoch_index, (offender.clone(), kind)),
+			Error::<T>::DuplicateOffenceReport
+		);
+		let slash = Self::compute_slash(&offender, kind)?;
+
+		let defer_epochs = T::SlashDeferEpochs::get();
+		if defer_epochs.is_zero() {
+			OffenceReports::<T>::insert(epoch_index, (offender.clone(), kind), ());
+			Self::apply_slash(slash, epoch_index);
+			return Ok(());
+		}
+
+		// A report rejected for a full queue is not recorded, so that it can be sent again
+		UnappliedSlashes::<T>::try_mutate(epoch_index, |slashes| slashes.try_push(slash))
+			.map_err(|_| Error::<T>::TooManyUnappliedSlashes)?;
+		OffenceReports::<T>::insert(epoch_index, (offender.clone(), kind), ());
+		if !NextSlashEpoch::<T>::exists() {
+			NextSlashEpoch::<T>::put(epoch_index);
+		}
+		Self::deposit_event(Event::SlashDeferred {
+			validator: offender,
+			kind,
+			epoch_index,
+			apply_at_epoch: epoch_index.saturating_add(defer_epochs),
+		});
+		Ok(())
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The fraction of the exposed stake that is slashed for an offence kind.
+	pub fn slash_fraction(kind: OffenceKind) -> Perbill {
+		match kind {
+			OffenceKind::Equivocation => T::EquivocationSlashFraction::get(),
+			OffenceKind::Unresponsiveness => T::UnresponsivenessSlashFraction::get(),
+		}
+	}
+
+	/// Compute the slash of a validator and its delegators from the exposures of the running epoch.
+	pub(crate) fn compute_slash(
+		offender: &T::AccountId,
+		kind: OffenceKind,
+	) -> DispatchResultWithValue<UnappliedSlash<T>> {
+		let epoch_index = EpochIndex::<T>::get();
+		let exposure = EpochExposures::<T>::get(epoch_index, offender)
+			.ok_or(Error::<T>::OffenderNotValidator)?;
+
+		let slash_fraction = Self::slash_fraction(kind);
+		let mut others = BoundedVec::default();
+		for (delegator, amount) in Self::exposure_delegators(epoch_index, offender, &exposure) {
+			// The exposed delegators of a validator come from its bounded delegator list
+			let _ = others.try_push((delegator, slash_fraction * amount));
+		}
+
+		Ok(UnappliedSlash {
+			validator: offender.clone(),
+			kind,
+			slash_fraction,
+			own: slash_fraction * exposure.bond,
+			others,
+		})
+	}
+
+	/// Apply up to `budget` items of the deferred slashes that are due. Returns the number of
+	/// processed items, which exceeds `budget` by at most `MaxCandidateDelegators` when the last
+	/// applied slash does not fit in the page.
+	pub(crate) fn apply_deferred_slashes(budget: u32) -> u32 {
+		let epoch_index = EpochIndex::<T>::get();
+		let mut processed = 0u32;
+		while processed < budget {
+			let Some(slash_epoch) = NextSlashEpoch::<T>::get() else { break };
+			if slash_epoch.saturating_add(T::SlashDeferEpochs::get()) > epoch_index {
+				break;
+			}
+
+			let mut slashes = UnappliedSlashes::<T>::take(slash_epoch);
+			while processed < budget {
+				let Some(slash) = slashes.pop() else { break };
+				processed =
+					processed.saturating_add(slash.others.len() as u32).saturating_add(1);
+				Self::apply_slash(slash, slash_epoch);
+			}
+
+			if slashes.is_empty() {
+				// Every slash of the epoch is applied or cancelled, move on to the next epoch
+				NextSlashEpoch::<T>::put(slash_epoch.saturating_add(1));
+				processed.saturating_inc();
+			} else {
+				UnappliedSlashes::<T>::insert(slash_epoch, slashes);
+			}
+		}
+		processed
+	}
+
+	/// Apply a computed slash to the validator and its delegators.
+	///
+	/// Every amount is capped by the stake that is still on hold at the time the slash is
+	/// applied, so stake that already left the pool can't be slashed twice.
+	pub(crate) fn apply_slash(slash: UnappliedSlash<T>, epoch_index: u32) {
+		let UnappliedSlash { validator, kind, slash_fraction, own, others } = slash;
+
+		let validator_amount = Self::slash_candidate_bond(&validator, own);
+		let mut delegators_amount: BalanceOf<T> = Zero::zero();
+		for (delegator, amount) in others.into_inner() {
+			let slashed = Self::slash_delegation(&delegator, &validator, amount);
+			if slashed.is_zero() {
+				continue;
+			}
+			delegators_amount = delegators_amount.saturating_add(slashed);
+			Self::deposit_event(Event::DelegatorSlashed {
+				delegator,
+				validator: validator.clone(),
+				amount: slashed,
+			});
+		}
+
+		Self::deposit_event(Event::Slashed {
+			validator,
+			kind,
+			slash_fraction,
+			epoch_index,
+			validator_amount,
+			delegators_amount,
+		});
+	}
+
+	/// Slash the bond of a candidate, taking from its pending bond decrease once the bond is
+	/// exhausted. Returns the amount actually slashed.
+	fn slash_candidate_bond(candidate: &T::AccountId, amount: BalanceOf<T>) -> BalanceOf<T> {
+		let mut remaining = amount;
+
+		if let Ok(mut candidate_detail) = Self::get_candidate(candidate) {
+			let from_bond = remaining.min(candidate_detail.bond);
+			let slashed = Self::slash_held(HoldReason::CandidateBondReserved, candidate, from_bond);
+			candidate_detail.update_bond(candidate_detail.bond.saturating_sub(slashed));
+			CandidatePool::<T>::set(candidate, Some(candidate_detail));
+			remaining = remaining.saturating_sub(slashed);
+		}
+
+		if !remaining.is_zero() {
+			if let Some(mut request) = CandidateBondLessRequests::<T>::get(candidate) {
+				let from_request = remaining.min(request.amount);
+				let slashed =
+					Self::slash_held(HoldReason::CandidateBondReserved, candidate, from_request);
+				request.amount = request.amount.saturating_sub(slashed);
+				if request.amount.is_zero() {
+					CandidateBondLessRequests::<T>::remove(candidate);
+				} else {
+					CandidateBondLessRequests::<T>::insert(candidate, request);
+				}
+				remaining = remaining.saturating_sub(slashed);
+			}
+		}
+
+		amount.saturating_sub(remaining)
+	}
+
+	/// Slash the delegation of a delegator to a validator, taking from its pending undelegation
+	/// once the delegation is exhausted. Returns the amount actually slashed.
+	fn slash_delegation(
+		delegator: &T::AccountId,
+		validator: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		let mut remaining = amount;
+
+		if let Ok(mut delegation_info) = Self::get_delegation(delegator, validator) {
+			let from_delegation = remaining.min(delegation_info.amount);
+			let slashed =
+				Self::slash_held(HoldReason::DelegateAmountReserved, delegator, from_delegation);
+			let new_delegated_amount = delegation_info.amount.saturating_sub(slashed);
+			if new_delegated_amount.is_zero() {
+				let _ = Self::remove_candidate_delegation_data(delegator, validator);
+			} else {
+				// A slashed delegation is allowed to stay below the minimum delegate amount
+				let _ = Self::update_candidate_delegator(
+					validator,
+					delegator,
+					&delegation_info.amount,
+					new_delegated_amount,
+				);
+				delegation_info.set_amount(new_delegated_amount);
+				DelegationInfos::<T>::set(delegator, validator, Some(delegation_info));
+			}
+			let _ = Self::decrease_candidate_delegations(validator, &slashed);
+			remaining = remaining.saturating_sub(slashed);
+		}
+
+		if !remaining.is_zero() {
+			let mut unbonding_requests = UnbondingDelegations::<T>::get(delegator);
+			if let Some(request) =
+				unbonding_requests.iter_mut().find(|request| &request.candidate == validator)
+			{
+				let from_request = remaining.min(request.amount);
+				let slashed =
+					Self::slash_held(HoldReason::DelegateAmountReserved, delegator, from_request);
+				request.amount = request.amount.saturating_sub(slashed);
+				remaining = remaining.saturating_sub(slashed);
+			}
+			unbonding_requests.retain(|request| !request.amount.is_zero());
+			if unbonding_requests.is_empty() {
+				UnbondingDelegations::<T>::remove(delegator);
+			} else {
+				UnbondingDelegations::<T>::insert(delegator, unbonding_requests);
+			}
+		}
+
+		amount.saturating_sub(remaining)
+	}
+
+	/// Take an amount from a hold of an account, burning it or moving it to the slash
+	/// destination. Returns the amount actually taken.
+	fn slash_held(reason: HoldReason, who: &T::AccountId, amount: BalanceOf<T>) -> BalanceOf<T> {
+		if amount.is_zero() {
+			return Zero::zero();
+		}
+		let reason: T::RuntimeHoldReason = reason.into();
+		let moved = T::SlashDestination::get().and_then(|destination| {
+			T::NativeBalance::transfer_on_hold(
+				&reason,
+				who,
+				&destination,
+				amount,
+				Precision::BestEffort,
+				Restriction::Free,
+				Fortitude::Force,
+			)
+			.ok()
+		});
+		// Burn the amount if there is no destination or it can't receive the funds
+		moved
+			.or_else(|| {
+				T::NativeBalance::burn_held(
+					&reason,
+					who,
+					amount,
+					Precision::BestEffort,
+					Fortitude::Force,
+				)
+				.ok()
+			})
+			.unwrap_or_else(Zero::zero)
+	}
+}
@@ -1,16 +1,1491 @@
-use crate::{mock::*, *};
-use frame_support::assert_ok;
+use crate::{mock::*, models::*, *};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{
+		fungible::{Balanced, InspectHold, MutateHold},
+		tokens::{Fortitude, Precision, Preservation},
+		Hooks, OnUnbalanced,
+	},
+};
+use sp_runtime::{DispatchError, Perbill, Percent, TokenError};
+
+/// The amount held from `who` for `reason`.
+fn held(reason: HoldReason, who: AccountId) -> Balance {
+	Balances::balance_on_hold(&reason.into(), &who)
+}
+
+/// The candidate `candidate_id` of the candidate pool.
+fn candidate(candidate_id: AccountId) -> Candidate<Test> {
+	CandidatePool::<Test>::get(candidate_id).expect("The candidate must be registered")
+}
+
+/// The amount delegated by `delegator` to `candidate_id`.
+fn delegated(delegator: AccountId, candidate_id: AccountId) -> Balance {
+	DelegationInfos::<Test>::get(delegator, candidate_id)
+		.map(|delegation| delegation.amount)
+		.unwrap_or_default()
+}
+
+fn total_issuance() -> Balance {
+	pallet_balances::TotalIssuance::<Test>::get()
+}
+
+fn register(candidate_id: AccountId, bond: Balance, commission: Perbill) {
+	assert_ok!(Dpos::register_as_candidate(RuntimeOrigin::signed(candidate_id), bond, commission));
+}
+
+fn delegate(delegator: AccountId, candidate_id: AccountId, amount: Balance) {
+	assert_ok!(Dpos::delegate(RuntimeOrigin::signed(delegator), candidate_id, amount));
+}
+
+fn validator_ids() -> Vec<AccountId> {
+	CurrentValidators::<Test>::get().iter().map(|(validator, _, _)| *validator).collect()
+}
+
+#[test]
+fn genesis_registers_candidates_and_elects_validators() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 2_000), (3, 3_000), (4, 4_000)])
+		.build_and_execute(|| {
+			assert_eq!(CandidatePool::<Test>::count(), 4);
+			assert_eq!(candidate(1), Candidate::new(1_000, Perbill::zero(), 0));
+			assert_eq!(held(HoldReason::CandidateBondReserved, 1), 1_000);
+
+			// The top candidates by stake are elected and reported
+			assert_eq!(
+				CurrentValidators::<Test>::get().into_inner(),
+				vec![(4, 4_000, 4_000), (3, 3_000, 3_000), (2, 2_000, 2_000)]
+			);
+			assert_eq!(ReportedValidators::get(), vec![4, 3, 2]);
+			assert!(EpochExposures::<Test>::get(0, 4).is_some());
+			assert!(EpochExposures::<Test>::get(0, 1).is_none());
+		});
+}
+
+#[test]
+#[should_panic(expected = "Candidate registration duplicates")]
+fn genesis_rejects_duplicate_candidates() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000), (1, 2_000)]).build();
+}
+
+#[test]
+fn genesis_registers_delegations() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 2_000)])
+		.genesis_delegations(vec![(10, 1, 500), (11, 1, 300), (10, 2, 200)])
+		.build_and_execute(|| {
+			assert_eq!(delegated(10, 1), 500);
+			assert_eq!(delegated(10, 2), 200);
+			assert_eq!(DelegateCountMap::<Test>::get(10), 2);
+			assert_eq!(DelegateCountMap::<Test>::get(11), 1);
+			assert_eq!(
+				CandidateDelegators::<Test>::get(1).into_inner(),
+				vec![(10, 500), (11, 300)]
+			);
+			assert_eq!(candidate(1).sum_delegation, 800);
+			assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 700);
+
+			// The delegations back the validators of the first epoch
+			assert_eq!(
+				CurrentValidators::<Test>::get().into_inner(),
+				vec![(2, 2_000, 2_200), (1, 1_000, 1_800)]
+			);
+			assert_eq!(
+				EpochExposures::<Test>::get(0, 1).map(|exposure| exposure.total),
+				Some(1_800)
+			);
+		});
+}
+
+#[test]
+fn genesis_starts_at_the_initial_epoch_index() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000)])
+		.initial_epoch_index(5)
+		.build_and_execute(|| {
+			assert_eq!(EpochIndex::<Test>::get(), 5);
+			assert_eq!(candidate(1), Candidate::new(1_000, Perbill::zero(), 5));
+			assert!(EpochExposures::<Test>::contains_key(5, 1));
+
+			run_to_epoch(1);
+			assert_eq!(EpochIndex::<Test>::get(), 6);
+		});
+}
+
+#[test]
+#[should_panic(expected = "Genesis candidate bond is below `MinCandidateBond`")]
+fn genesis_rejects_a_low_candidate_bond() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 499)]).build();
+}
+
+#[test]
+#[should_panic(expected = "Failed to hold the bond of genesis candidate 21")]
+fn genesis_rejects_an_unfunded_candidate_bond() {
+	ExtBuilder::default().balance(21, 600).genesis_candidates(vec![(21, 1_000)]).build();
+}
+
+#[test]
+#[should_panic(expected = "Genesis delegation to a candidate that is not registered")]
+fn genesis_rejects_a_delegation_to_an_unknown_candidate() {
+	ExtBuilder::default().genesis_delegations(vec![(10, 1, 500)]).build();
+}
+
+#[test]
+#[should_panic(expected = "Genesis delegation duplicates")]
+fn genesis_rejects_duplicate_delegations() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000)])
+		.genesis_delegations(vec![(10, 1, 500), (10, 1, 200)])
+		.build();
+}
+
+#[test]
+#[should_panic(expected = "Genesis delegation is below `MinDelegateAmount`")]
+fn genesis_rejects_a_low_delegation() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000)])
+		.genesis_delegations(vec![(10, 1, 99)])
+		.build();
+}
+
+#[test]
+#[should_panic(expected = "Genesis delegator delegates to more than `MaxDelegateCount` candidates")]
+fn genesis_rejects_too_many_delegations_of_a_delegator() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 1_000), (3, 1_000)])
+		.genesis_delegations(vec![(10, 1, 100), (10, 2, 100), (10, 3, 100)])
+		.build();
+}
+
+#[test]
+#[should_panic(expected = "Genesis candidate has more than `MaxCandidateDelegators` delegators")]
+fn genesis_rejects_too_many_delegators_of_a_candidate() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000)])
+		.genesis_delegations(vec![(10, 1, 100), (11, 1, 100), (12, 1, 100), (13, 1, 100)])
+		.build();
+}
+
+#[test]
+#[should_panic(expected = "Failed to hold the genesis delegation of 21")]
+fn genesis_rejects_an_unfunded_delegation() {
+	ExtBuilder::default()
+		.balance(21, 150)
+		.genesis_candidates(vec![(1, 1_000)])
+		.genesis_delegations(vec![(21, 1, 200)])
+		.build();
+}
 
 #[test]
-fn it_works_for_default_value() {
+fn force_report_new_validators_works() {
 	new_test_ext().execute_with(|| {
-		// Go past genesis block so events get deposited
-		System::set_block_number(1);
-		// Dispatch a signed extrinsic.
-		assert_ok!(Dpos::do_something(RuntimeOrigin::signed(1), 43));
-		// Read pallet storage and assert an expected result.
-		assert_eq!(Something::<Test>::get(), Some(43));
-		// Assert that the correct event was deposited
-		System::assert_last_event(Event::SomethingStored { something: 43, who: 1 }.into());
-	});
-}
\ No newline at end of file
+		assert_ok!(Dpos::force_report_new_validators(RuntimeOrigin::root(), vec![1, 2]));
+		assert_eq!(ReportedValidators::get(), vec![1, 2]);
+	});
+}
+
+#[test]
+fn force_report_new_validators_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Dpos::force_report_new_validators(RuntimeOrigin::signed(1), vec![1]),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Dpos::force_report_new_validators(RuntimeOrigin::root(), vec![1, 2, 3]),
+			Error::<Test>::TooManyValidators
+		);
+	});
+}
+
+#[test]
+fn register_as_candidate_works() {
+	new_test_ext().execute_with(|| {
+		register(1, 1_000, Perbill::from_percent(10));
+
+		assert_eq!(candidate(1), Candidate::new(1_000, Perbill::from_percent(10), 0));
+		assert_eq!(held(HoldReason::CandidateBondReserved, 1), 1_000);
+		System::assert_last_event(
+			Event::CandidateRegistered {
+				candidate_id: 1,
+				initial_bond: 1_000,
+				commission: Perbill::from_percent(10),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn register_as_candidate_fails() {
+	new_test_ext().execute_with(|| {
+		let origin = RuntimeOrigin::signed(1);
+		assert_noop!(
+			Dpos::register_as_candidate(origin.clone(), 0, Perbill::zero()),
+			Error::<Test>::InvalidZeroAmount
+		);
+		assert_noop!(
+			Dpos::register_as_candidate(origin.clone(), 499, Perbill::zero()),
+			Error::<Test>::BelowMinimumCandidateBond
+		);
+		assert_noop!(
+			Dpos::register_as_candidate(origin.clone(), 1_000, Perbill::from_percent(51)),
+			Error::<Test>::CommissionTooHigh
+		);
+
+		register(1, 1_000, Perbill::zero());
+		assert_noop!(
+			Dpos::register_as_candidate(origin, 1_000, Perbill::zero()),
+			Error::<Test>::CandidateAlreadyExist
+		);
+
+		// The candidate pool is bounded by `MaxCandidates`
+		for candidate_id in 2..=MaxCandidates::get() as AccountId {
+			register(candidate_id, 1_000, Perbill::zero());
+		}
+		assert_noop!(
+			Dpos::register_as_candidate(RuntimeOrigin::signed(11), 1_000, Perbill::zero()),
+			Error::<Test>::TooManyValidators
+		);
+	});
+}
+
+#[test]
+fn delegate_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+
+		assert_eq!(delegated(10, 1), 500);
+		assert_eq!(DelegateCountMap::<Test>::get(10), 1);
+		assert_eq!(CandidateDelegators::<Test>::get(1).into_inner(), vec![(10, 500)]);
+		assert_eq!(candidate(1).sum_delegation, 500);
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 500);
+		System::assert_last_event(
+			Event::CandidateDelegated {
+				candidate_id: 1,
+				delegated_by: 10,
+				amount: 500,
+				total_delegated_amount: 500,
+			}
+			.into(),
+		);
+
+		// Delegating again adds to the existing delegation
+		delegate(10, 1, 200);
+		assert_eq!(delegated(10, 1), 700);
+		assert_eq!(DelegateCountMap::<Test>::get(10), 1);
+		assert_eq!(CandidateDelegators::<Test>::get(1).into_inner(), vec![(10, 700)]);
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 700);
+	});
+}
+
+#[test]
+fn delegate_fails() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 1_000), (3, 1_000)])
+		.build_and_execute(|| {
+			let origin = RuntimeOrigin::signed(10);
+			assert_noop!(Dpos::delegate(origin.clone(), 1, 0), Error::<Test>::InvalidZeroAmount);
+			assert_noop!(
+				Dpos::delegate(origin.clone(), 5, 500),
+				Error::<Test>::CandidateDoesNotExist
+			);
+			assert_noop!(
+				Dpos::delegate(origin.clone(), 1, 99),
+				Error::<Test>::BelowMinimumDelegateAmount
+			);
+
+			// A delegator can delegate to at most `MaxDelegateCount` candidates
+			delegate(10, 1, 500);
+			delegate(10, 2, 500);
+			assert_noop!(
+				Dpos::delegate(origin, 3, 500),
+				Error::<Test>::TooManyCandidateDelegations
+			);
+
+			assert_ok!(Dpos::leave_candidate_pool(RuntimeOrigin::signed(3)));
+			assert_noop!(
+				Dpos::delegate(RuntimeOrigin::signed(11), 3, 500),
+				Error::<Test>::CandidateIsLeaving
+			);
+		});
+}
+
+#[test]
+fn delegate_fails_without_enough_free_balance() {
+	ExtBuilder::default()
+		.balance(21, 150)
+		.genesis_candidates(vec![(1, 1_000)])
+		.build_and_execute(|| {
+			assert_noop!(
+				Dpos::delegate(RuntimeOrigin::signed(21), 1, 200),
+				TokenError::FundsUnavailable
+			);
+		});
+}
+
+#[test]
+fn delegate_kicks_the_lowest_delegator_of_a_full_candidate() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 300);
+		delegate(11, 1, 200);
+		delegate(12, 1, 100);
+
+		// The new delegation must be higher than the lowest one
+		assert_noop!(
+			Dpos::delegate(RuntimeOrigin::signed(13), 1, 100),
+			Error::<Test>::TooManyDelegatorsInPool
+		);
+
+		delegate(13, 1, 150);
+		System::assert_has_event(
+			Event::DelegatorKicked { candidate_id: 1, delegator: 12, amount: 100, unlock_epoch: 2 }
+				.into(),
+		);
+		assert_eq!(
+			CandidateDelegators::<Test>::get(1).into_inner(),
+			vec![(10, 300), (11, 200), (13, 150)]
+		);
+		assert_eq!(candidate(1).sum_delegation, 650);
+		assert!(DelegationInfos::<Test>::get(12, 1).is_none());
+		assert_eq!(DelegateCountMap::<Test>::get(12), 0);
+
+		// The kicked amount unbonds like an undelegation
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 12), 100);
+		assert_eq!(
+			UnbondingDelegations::<Test>::get(12).into_inner(),
+			vec![UnbondingRequest::new(1, 100, 2)]
+		);
+		run_to_epoch(2);
+		assert_ok!(Dpos::execute_undelegate(RuntimeOrigin::signed(12)));
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 12), 0);
+	});
+}
+
+#[test]
+fn kicked_delegators_are_slashed_for_the_epoch_they_were_exposed_in() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 300);
+		delegate(11, 1, 200);
+		delegate(12, 1, 100);
+		run_to_epoch(1);
+
+		// The delegator is kicked by a higher delegation after the offence was reported
+		assert_ok!(Dpos::report_offence(1, OffenceKind::Equivocation));
+		delegate(13, 1, 150);
+		assert!(DelegationInfos::<Test>::get(12, 1).is_none());
+		assert_noop!(
+			Dpos::execute_undelegate(RuntimeOrigin::signed(12)),
+			Error::<Test>::NoUnbondedDelegationFound
+		);
+
+		run_to_epoch(2);
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 12), 90);
+		assert_eq!(
+			UnbondingDelegations::<Test>::get(12).into_inner(),
+			vec![UnbondingRequest::new(1, 90, 3)]
+		);
+		System::assert_has_event(
+			Event::DelegatorSlashed { delegator: 12, validator: 1, amount: 10 }.into(),
+		);
+	});
+}
+
+#[test]
+fn unregister_as_candidate_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+		Rewards::<Test>::insert(1, 300);
+
+		assert_ok!(Dpos::unregister_as_candidate(RuntimeOrigin::root(), 1));
+
+		assert!(!CandidatePool::<Test>::contains_key(1));
+		assert!(CandidateDelegators::<Test>::get(1).is_empty());
+		assert!(DelegationInfos::<Test>::get(10, 1).is_none());
+		assert_eq!(DelegateCountMap::<Test>::get(10), 0);
+		assert_eq!(held(HoldReason::CandidateBondReserved, 1), 0);
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 0);
+		// The pending rewards of the candidate are paid out
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE + 300);
+		System::assert_has_event(Event::RewardClaimed { claimer: 1, total_reward: 300 }.into());
+		System::assert_last_event(Event::CandidateRegistrationRemoved { candidate_id: 1 }.into());
+	});
+}
+
+#[test]
+fn unregister_as_candidate_fails() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		assert_noop!(
+			Dpos::unregister_as_candidate(RuntimeOrigin::signed(1), 1),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Dpos::unregister_as_candidate(RuntimeOrigin::root(), 5),
+			Error::<Test>::CandidateDoesNotExist
+		);
+	});
+}
+
+#[test]
+fn undelegate_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+
+		assert_ok!(Dpos::undelegate(RuntimeOrigin::root(), 10, 1, 200));
+		assert_eq!(delegated(10, 1), 300);
+		assert_eq!(candidate(1).sum_delegation, 300);
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 300);
+		System::assert_last_event(
+			Event::CandidateUndelegated {
+				candidate_id: 1,
+				delegator: 10,
+				amount: 200,
+				left_delegated_amount: 300,
+			}
+			.into(),
+		);
+
+		// Undelegating everything removes the delegation
+		assert_ok!(Dpos::undelegate(RuntimeOrigin::root(), 10, 1, 300));
+		assert!(DelegationInfos::<Test>::get(10, 1).is_none());
+		assert_eq!(DelegateCountMap::<Test>::get(10), 0);
+		assert!(CandidateDelegators::<Test>::get(1).is_empty());
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 0);
+	});
+}
+
+#[test]
+fn undelegate_fails() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+
+		assert_noop!(
+			Dpos::undelegate(RuntimeOrigin::signed(10), 10, 1, 200),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Dpos::undelegate(RuntimeOrigin::root(), 10, 5, 200),
+			Error::<Test>::CandidateDoesNotExist
+		);
+		assert_noop!(
+			Dpos::undelegate(RuntimeOrigin::root(), 10, 1, 0),
+			Error::<Test>::InvalidZeroAmount
+		);
+		assert_noop!(
+			Dpos::undelegate(RuntimeOrigin::root(), 11, 1, 200),
+			Error::<Test>::DelegationDoesNotExist
+		);
+		assert_noop!(
+			Dpos::undelegate(RuntimeOrigin::root(), 10, 1, 600),
+			Error::<Test>::InvalidMinimumDelegateAmount
+		);
+		// The remaining delegation must stay above `MinDelegateAmount`
+		assert_noop!(
+			Dpos::undelegate(RuntimeOrigin::root(), 10, 1, 450),
+			Error::<Test>::BelowMinimumDelegateAmount
+		);
+	});
+}
+
+#[test]
+fn claim_reward_mints_the_accrued_rewards() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		BlockAuthor::set(Some(1));
+		run_to_block(2);
+		assert_eq!(Rewards::<Test>::get(1), 1_000);
+
+		let issuance = total_issuance();
+		assert_ok!(Dpos::claim_reward(RuntimeOrigin::signed(1)));
+
+		assert!(!Rewards::<Test>::contains_key(1));
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE);
+		assert_eq!(total_issuance(), issuance + 1_000);
+		System::assert_last_event(Event::RewardClaimed { claimer: 1, total_reward: 1_000 }.into());
+
+		assert_noop!(
+			Dpos::claim_reward(RuntimeOrigin::signed(1)),
+			Error::<Test>::NoClaimableRewardFound
+		);
+	});
+}
+
+#[test]
+fn claim_reward_pays_the_fees_from_the_reward_pot() {
+	ExtBuilder::default().treasury_cut(Perbill::from_percent(10)).build_and_execute(|| {
+		BlockAuthor::set(Some(1));
+		let issuance = total_issuance();
+		let fees = <Balances as Balanced<AccountId>>::withdraw(
+			&20,
+			1_000,
+			Precision::Exact,
+			Preservation::Expendable,
+			Fortitude::Polite,
+		)
+		.unwrap();
+
+		// The fees are moved to the treasury and the reward pot, not burnt
+		DealWithFees::<Test>::on_unbalanced(fees);
+		assert_eq!(total_issuance(), issuance);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), 100);
+		assert_eq!(Balances::free_balance(Dpos::reward_pot_account()), 900);
+		assert_eq!(Rewards::<Test>::get(1), 900);
+
+		// The claim is paid from the reward pot instead of being minted
+		assert_ok!(Dpos::claim_reward(RuntimeOrigin::signed(1)));
+		assert_eq!(total_issuance(), issuance);
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE + 900);
+		assert_eq!(Balances::free_balance(Dpos::reward_pot_account()), 0);
+	});
+}
+
+#[test]
+fn leave_candidate_pool_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		assert_ok!(Dpos::leave_candidate_pool(RuntimeOrigin::signed(1)));
+
+		assert_eq!(candidate(1).status, CandidateStatus::Leaving(2));
+		System::assert_last_event(
+			Event::CandidateLeavingScheduled { candidate_id: 1, exit_epoch: 2 }.into(),
+		);
+
+		assert_noop!(
+			Dpos::leave_candidate_pool(RuntimeOrigin::signed(1)),
+			Error::<Test>::CandidateAlreadyLeaving
+		);
+		assert_noop!(
+			Dpos::leave_candidate_pool(RuntimeOrigin::signed(5)),
+			Error::<Test>::CandidateDoesNotExist
+		);
+	});
+}
+
+#[test]
+fn execute_leave_candidates_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000), (2, 1_000)]).build_and_execute(
+		|| {
+			delegate(10, 1, 500);
+			assert_ok!(Dpos::leave_candidate_pool(RuntimeOrigin::signed(1)));
+
+			let origin = RuntimeOrigin::signed(20);
+			assert_noop!(
+				Dpos::execute_leave_candidates(origin.clone(), 1),
+				Error::<Test>::CandidateCannotLeaveYet
+			);
+			assert_noop!(
+				Dpos::execute_leave_candidates(origin.clone(), 2),
+				Error::<Test>::CandidateNotLeaving
+			);
+			assert_noop!(
+				Dpos::execute_leave_candidates(origin.clone(), 5),
+				Error::<Test>::CandidateDoesNotExist
+			);
+
+			// The leaving candidate is not elected anymore
+			run_to_epoch(1);
+			assert_eq!(validator_ids(), vec![2]);
+
+			run_to_epoch(2);
+			assert_ok!(Dpos::execute_leave_candidates(origin, 1));
+			assert!(!CandidatePool::<Test>::contains_key(1));
+			assert!(DelegationInfos::<Test>::get(10, 1).is_none());
+			assert_eq!(held(HoldReason::CandidateBondReserved, 1), 0);
+			assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 0);
+			System::assert_last_event(
+				Event::CandidateRegistrationRemoved { candidate_id: 1 }.into(),
+			);
+		},
+	);
+}
+
+#[test]
+fn schedule_undelegate_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+
+		assert_ok!(Dpos::schedule_undelegate(RuntimeOrigin::signed(10), 1, 200));
+
+		assert_eq!(delegated(10, 1), 300);
+		assert_eq!(candidate(1).sum_delegation, 300);
+		assert_eq!(
+			UnbondingDelegations::<Test>::get(10).into_inner(),
+			vec![UnbondingRequest::new(1, 200, 2)]
+		);
+		// The amount stays on hold until the request is executed
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 500);
+		System::assert_last_event(
+			Event::UndelegateScheduled {
+				candidate_id: 1,
+				delegator: 10,
+				amount: 200,
+				unlock_epoch: 2,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn schedule_undelegate_fails() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 1_000), (3, 1_000)])
+		.build_and_execute(|| {
+			delegate(10, 1, 500);
+			let origin = RuntimeOrigin::signed(10);
+
+			assert_noop!(
+				Dpos::schedule_undelegate(origin.clone(), 1, 0),
+				Error::<Test>::InvalidZeroAmount
+			);
+			assert_noop!(
+				Dpos::schedule_undelegate(origin.clone(), 2, 200),
+				Error::<Test>::DelegationDoesNotExist
+			);
+			assert_noop!(
+				Dpos::schedule_undelegate(origin.clone(), 1, 600),
+				Error::<Test>::InvalidMinimumDelegateAmount
+			);
+			assert_noop!(
+				Dpos::schedule_undelegate(origin.clone(), 1, 450),
+				Error::<Test>::BelowMinimumDelegateAmount
+			);
+
+			assert_ok!(Dpos::schedule_undelegate(origin.clone(), 1, 200));
+			assert_noop!(
+				Dpos::schedule_undelegate(origin, 1, 100),
+				Error::<Test>::PendingUndelegateRequestExists
+			);
+
+			// The unbonding queue is bounded by `MaxDelegateCount`
+			let origin = RuntimeOrigin::signed(11);
+			delegate(11, 1, 100);
+			delegate(11, 2, 100);
+			assert_ok!(Dpos::schedule_undelegate(origin.clone(), 1, 100));
+			assert_ok!(Dpos::schedule_undelegate(origin.clone(), 2, 100));
+			delegate(11, 3, 100);
+			assert_noop!(
+				Dpos::schedule_undelegate(origin, 3, 100),
+				Error::<Test>::TooManyUnbondingRequests
+			);
+		});
+}
+
+#[test]
+fn execute_undelegate_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+		assert_ok!(Dpos::schedule_undelegate(RuntimeOrigin::signed(10), 1, 200));
+
+		assert_noop!(
+			Dpos::execute_undelegate(RuntimeOrigin::signed(10)),
+			Error::<Test>::NoUnbondedDelegationFound
+		);
+
+		run_to_epoch(2);
+		assert_ok!(Dpos::execute_undelegate(RuntimeOrigin::signed(10)));
+
+		assert!(UnbondingDelegations::<Test>::get(10).is_empty());
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 300);
+		System::assert_last_event(
+			Event::UndelegateExecuted { candidate_id: 1, delegator: 10, amount: 200 }.into(),
+		);
+	});
+}
+
+#[test]
+fn cancel_undelegate_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+		assert_ok!(Dpos::schedule_undelegate(RuntimeOrigin::signed(10), 1, 200));
+
+		assert_ok!(Dpos::cancel_undelegate(RuntimeOrigin::signed(10), 1));
+
+		assert_eq!(delegated(10, 1), 500);
+		assert_eq!(candidate(1).sum_delegation, 500);
+		assert!(UnbondingDelegations::<Test>::get(10).is_empty());
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 500);
+		System::assert_last_event(
+			Event::UndelegateCancelled {
+				candidate_id: 1,
+				delegator: 10,
+				amount: 200,
+				total_delegated_amount: 500,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn cancel_undelegate_fails() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		assert_noop!(
+			Dpos::cancel_undelegate(RuntimeOrigin::signed(10), 1),
+			Error::<Test>::UndelegateRequestDoesNotExist
+		);
+
+		delegate(10, 1, 500);
+		assert_ok!(Dpos::schedule_undelegate(RuntimeOrigin::signed(10), 1, 200));
+		assert_ok!(Dpos::leave_candidate_pool(RuntimeOrigin::signed(1)));
+		assert_noop!(
+			Dpos::cancel_undelegate(RuntimeOrigin::signed(10), 1),
+			Error::<Test>::CandidateIsLeaving
+		);
+	});
+}
+
+#[test]
+fn candidate_bond_more_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		assert_ok!(Dpos::candidate_bond_more(RuntimeOrigin::signed(1), 500));
+
+		assert_eq!(candidate(1).bond, 1_500);
+		assert_eq!(held(HoldReason::CandidateBondReserved, 1), 1_500);
+		System::assert_last_event(
+			Event::CandidateBondIncreased { candidate_id: 1, amount: 500, new_bond: 1_500 }.into(),
+		);
+	});
+}
+
+#[test]
+fn candidate_bond_more_fails() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		assert_noop!(
+			Dpos::candidate_bond_more(RuntimeOrigin::signed(1), 0),
+			Error::<Test>::InvalidZeroAmount
+		);
+		assert_noop!(
+			Dpos::candidate_bond_more(RuntimeOrigin::signed(5), 500),
+			Error::<Test>::CandidateDoesNotExist
+		);
+
+		assert_ok!(Dpos::leave_candidate_pool(RuntimeOrigin::signed(1)));
+		assert_noop!(
+			Dpos::candidate_bond_more(RuntimeOrigin::signed(1), 500),
+			Error::<Test>::CandidateIsLeaving
+		);
+	});
+}
+
+#[test]
+fn schedule_candidate_bond_less_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		assert_ok!(Dpos::schedule_candidate_bond_less(RuntimeOrigin::signed(1), 300));
+
+		assert_eq!(candidate(1).bond, 700);
+		assert_eq!(CandidateBondLessRequests::<Test>::get(1), Some(BondLessRequest::new(300, 2)));
+		// The amount stays on hold until the request is executed
+		assert_eq!(held(HoldReason::CandidateBondReserved, 1), 1_000);
+		System::assert_last_event(
+			Event::CandidateBondLessScheduled {
+				candidate_id: 1,
+				amount: 300,
+				new_bond: 700,
+				unlock_epoch: 2,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn schedule_candidate_bond_less_fails() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		let origin = RuntimeOrigin::signed(1);
+		assert_noop!(
+			Dpos::schedule_candidate_bond_less(origin.clone(), 0),
+			Error::<Test>::InvalidZeroAmount
+		);
+		assert_noop!(
+			Dpos::schedule_candidate_bond_less(RuntimeOrigin::signed(5), 100),
+			Error::<Test>::CandidateDoesNotExist
+		);
+		assert_noop!(
+			Dpos::schedule_candidate_bond_less(origin.clone(), 600),
+			Error::<Test>::BelowMinimumCandidateBond
+		);
+
+		assert_ok!(Dpos::schedule_candidate_bond_less(origin.clone(), 100));
+		assert_noop!(
+			Dpos::schedule_candidate_bond_less(origin.clone(), 100),
+			Error::<Test>::PendingBondLessRequestExists
+		);
+
+		assert_ok!(Dpos::leave_candidate_pool(origin.clone()));
+		assert_noop!(
+			Dpos::schedule_candidate_bond_less(origin, 100),
+			Error::<Test>::CandidateIsLeaving
+		);
+	});
+}
+
+#[test]
+fn execute_candidate_bond_less_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		let origin = RuntimeOrigin::signed(1);
+		assert_noop!(
+			Dpos::execute_candidate_bond_less(origin.clone()),
+			Error::<Test>::BondLessRequestDoesNotExist
+		);
+
+		assert_ok!(Dpos::schedule_candidate_bond_less(origin.clone(), 300));
+		assert_noop!(
+			Dpos::execute_candidate_bond_less(origin.clone()),
+			Error::<Test>::BondLessRequestNotUnlocked
+		);
+
+		run_to_epoch(2);
+		assert_ok!(Dpos::execute_candidate_bond_less(origin));
+		assert!(CandidateBondLessRequests::<Test>::get(1).is_none());
+		assert_eq!(held(HoldReason::CandidateBondReserved, 1), 700);
+		System::assert_last_event(
+			Event::CandidateBondLessExecuted { candidate_id: 1, amount: 300 }.into(),
+		);
+	});
+}
+
+#[test]
+fn cancel_candidate_bond_less_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		let origin = RuntimeOrigin::signed(1);
+		assert_noop!(
+			Dpos::cancel_candidate_bond_less(origin.clone()),
+			Error::<Test>::BondLessRequestDoesNotExist
+		);
+
+		assert_ok!(Dpos::schedule_candidate_bond_less(origin.clone(), 300));
+		assert_ok!(Dpos::cancel_candidate_bond_less(origin));
+
+		assert_eq!(candidate(1).bond, 1_000);
+		assert!(CandidateBondLessRequests::<Test>::get(1).is_none());
+		assert_eq!(held(HoldReason::CandidateBondReserved, 1), 1_000);
+		System::assert_last_event(
+			Event::CandidateBondLessCancelled { candidate_id: 1, amount: 300, new_bond: 1_000 }
+				.into(),
+		);
+	});
+}
+
+#[test]
+fn set_auto_compound_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		assert_noop!(
+			Dpos::set_auto_compound(RuntimeOrigin::signed(10), 1, Percent::from_percent(50)),
+			Error::<Test>::DelegationDoesNotExist
+		);
+
+		delegate(10, 1, 500);
+		assert_ok!(Dpos::set_auto_compound(
+			RuntimeOrigin::signed(10),
+			1,
+			Percent::from_percent(50)
+		));
+
+		assert_eq!(
+			DelegationInfos::<Test>::get(10, 1).map(|delegation| delegation.auto_compound),
+			Some(Percent::from_percent(50))
+		);
+		System::assert_last_event(
+			Event::AutoCompoundSet {
+				candidate_id: 1,
+				delegator: 10,
+				value: Percent::from_percent(50),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn set_commission_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		let origin = RuntimeOrigin::signed(1);
+		// The commission was set at genesis in this epoch
+		assert_noop!(
+			Dpos::set_commission(origin.clone(), Perbill::from_percent(20)),
+			Error::<Test>::CommissionUpdateTooSoon
+		);
+
+		run_to_epoch(1);
+		assert_noop!(
+			Dpos::set_commission(origin.clone(), Perbill::from_percent(51)),
+			Error::<Test>::CommissionTooHigh
+		);
+		assert_noop!(
+			Dpos::set_commission(RuntimeOrigin::signed(5), Perbill::from_percent(20)),
+			Error::<Test>::CandidateDoesNotExist
+		);
+
+		assert_ok!(Dpos::set_commission(origin, Perbill::from_percent(20)));
+		assert_eq!(candidate(1).commission, Perbill::from_percent(20));
+		assert_eq!(candidate(1).commission_updated_at, 1);
+		System::assert_last_event(
+			Event::CommissionUpdated { candidate_id: 1, commission: Perbill::from_percent(20) }
+				.into(),
+		);
+	});
+}
+
+#[test]
+fn set_candidate_profile_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		let origin = RuntimeOrigin::signed(1);
+		assert_ok!(Dpos::set_candidate_profile(
+			origin.clone(),
+			b"alice".to_vec(),
+			b"a.io".to_vec(),
+			b"a@a".to_vec(),
+			b"p2p".to_vec(),
+		));
+
+		// Each field takes its bytes and a single byte of length prefix
+		assert_eq!(held(HoldReason::ProfileDeposit, 1), 19);
+		assert_eq!(CandidateProfiles::<Test>::get(1).map(|(_, deposit)| deposit), Some(19));
+		System::assert_last_event(
+			Event::CandidateProfileSet { candidate_id: 1, deposit: 19 }.into(),
+		);
+
+		// A shorter profile releases the difference
+		assert_ok!(Dpos::set_candidate_profile(origin, b"al".to_vec(), vec![], vec![], vec![]));
+		assert_eq!(held(HoldReason::ProfileDeposit, 1), 6);
+
+		// The deposit is released when the candidate leaves the pool
+		assert_ok!(Dpos::unregister_as_candidate(RuntimeOrigin::root(), 1));
+		assert!(CandidateProfiles::<Test>::get(1).is_none());
+		assert_eq!(held(HoldReason::ProfileDeposit, 1), 0);
+		System::assert_has_event(
+			Event::CandidateProfileCleared { candidate_id: 1, deposit: 6 }.into(),
+		);
+	});
+}
+
+#[test]
+fn set_candidate_profile_fails() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		assert_noop!(
+			Dpos::set_candidate_profile(RuntimeOrigin::signed(5), vec![], vec![], vec![], vec![]),
+			Error::<Test>::CandidateDoesNotExist
+		);
+		let too_long = vec![b'x'; MaxProfileFieldLength::get() as usize + 1];
+		assert_noop!(
+			Dpos::set_candidate_profile(RuntimeOrigin::signed(1), too_long, vec![], vec![], vec![]),
+			Error::<Test>::ProfileFieldTooLong
+		);
+	});
+}
+
+#[test]
+fn report_offence_slashes_right_away_without_defer() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000)])
+		.slash_defer_epochs(0)
+		.build_and_execute(|| {
+			// Only the delegations of the running epoch exposures are slashed
+			delegate(10, 1, 1_000);
+			run_to_epoch(1);
+			let issuance = total_issuance();
+
+			assert_ok!(Dpos::report_offence(1, OffenceKind::Equivocation));
+
+			assert_eq!(candidate(1).bond, 900);
+			assert_eq!(candidate(1).sum_delegation, 900);
+			assert_eq!(delegated(10, 1), 900);
+			assert_eq!(held(HoldReason::CandidateBondReserved, 1), 900);
+			assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 900);
+			// The slashed amounts are burnt without a slash destination
+			assert_eq!(total_issuance(), issuance - 200);
+			System::assert_has_event(
+				Event::DelegatorSlashed { delegator: 10, validator: 1, amount: 100 }.into(),
+			);
+			System::assert_last_event(
+				Event::Slashed {
+					validator: 1,
+					kind: OffenceKind::Equivocation,
+					slash_fraction: Perbill::from_percent(10),
+					epoch_index: 1,
+					validator_amount: 100,
+					delegators_amount: 100,
+				}
+				.into(),
+			);
+		});
+}
+
+#[test]
+fn report_offence_moves_the_slash_to_the_slash_destination() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000)])
+		.slash_defer_epochs(0)
+		.build_and_execute(|| {
+			SlashDestination::set(Some(100));
+			let issuance = total_issuance();
+
+			assert_ok!(Dpos::report_offence(1, OffenceKind::Unresponsiveness));
+
+			assert_eq!(candidate(1).bond, 990);
+			assert_eq!(Balances::free_balance(100), 10);
+			assert_eq!(total_issuance(), issuance);
+		});
+}
+
+#[test]
+fn report_offence_defers_the_slash() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		assert_ok!(Dpos::report_offence(1, OffenceKind::Equivocation));
+
+		assert_eq!(candidate(1).bond, 1_000);
+		assert_eq!(UnappliedSlashes::<Test>::get(0).len(), 1);
+		System::assert_last_event(
+			Event::SlashDeferred {
+				validator: 1,
+				kind: OffenceKind::Equivocation,
+				epoch_index: 0,
+				apply_at_epoch: 1,
+			}
+			.into(),
+		);
+
+		// The slash is applied `SlashDeferEpochs` epochs later
+		run_to_epoch(1);
+		assert_eq!(candidate(1).bond, 900);
+		assert!(UnappliedSlashes::<Test>::get(0).is_empty());
+		System::assert_has_event(
+			Event::Slashed {
+				validator: 1,
+				kind: OffenceKind::Equivocation,
+				slash_fraction: Perbill::from_percent(10),
+				epoch_index: 0,
+				validator_amount: 100,
+				delegators_amount: 0,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn deferred_slashes_reach_the_delegations_unbonding_after_the_offence() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+		run_to_epoch(1);
+
+		// The whole delegation starts unbonding right after the offence is reported
+		assert_ok!(Dpos::report_offence(1, OffenceKind::Equivocation));
+		assert_ok!(Dpos::schedule_undelegate(RuntimeOrigin::signed(10), 1, 500));
+
+		// The slash lands before the unbonding period ends
+		run_to_epoch(2);
+		assert_noop!(
+			Dpos::execute_undelegate(RuntimeOrigin::signed(10)),
+			Error::<Test>::NoUnbondedDelegationFound
+		);
+		assert_eq!(
+			UnbondingDelegations::<Test>::get(10).into_inner(),
+			vec![UnbondingRequest::new(1, 450, 3)]
+		);
+
+		run_to_epoch(3);
+		assert_ok!(Dpos::execute_undelegate(RuntimeOrigin::signed(10)));
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 0);
+		assert_eq!(Balances::free_balance(10), INITIAL_BALANCE - 50);
+	});
+}
+
+#[test]
+#[should_panic(expected = "The leaving stake must stay on hold")]
+fn integrity_test_rejects_an_unbonding_period_within_the_slash_defer() {
+	ExtBuilder::default().slash_defer_epochs(UnbondingEpochs::get()).build().execute_with(|| {
+		<Dpos as Hooks<BlockNumber>>::integrity_test();
+	});
+}
+
+#[test]
+fn deferred_slashes_are_applied_in_pages() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000), (2, 2_000)]).build_and_execute(
+		|| {
+			for delegator in 10..13 {
+				delegate(delegator, 1, 100);
+				delegate(delegator + 3, 2, 100);
+			}
+			run_to_epoch(1);
+			assert_ok!(Dpos::report_offence(1, OffenceKind::Equivocation));
+			assert_ok!(Dpos::report_offence(2, OffenceKind::Equivocation));
+			assert_eq!(NextSlashEpoch::<Test>::get(), Some(1));
+
+			// A slash of a validator and its three delegators fills the page of four items, the
+			// last slash of the epoch is applied first
+			run_to_epoch(2);
+			assert_eq!(candidate(2).bond, 1_800);
+			assert_eq!(delegated(13, 2), 90);
+			assert_eq!(candidate(1).bond, 1_000);
+			assert_eq!(delegated(10, 1), 100);
+			assert_eq!(UnappliedSlashes::<Test>::get(1).len(), 1);
+
+			run_to_block(System::block_number() + 1);
+			assert_eq!(candidate(1).bond, 900);
+			assert_eq!(delegated(10, 1), 90);
+			assert!(!UnappliedSlashes::<Test>::contains_key(1));
+			assert_eq!(NextSlashEpoch::<Test>::get(), Some(2));
+		},
+	);
+}
+
+#[test]
+fn report_offence_fails() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 2_000), (3, 3_000), (4, 4_000)])
+		.build_and_execute(|| {
+			// The candidate with the lowest stake is not in the active set
+			assert_noop!(
+				Dpos::report_offence(1, OffenceKind::Equivocation),
+				Error::<Test>::OffenderNotValidator
+			);
+
+			assert_ok!(Dpos::report_offence(4, OffenceKind::Equivocation));
+			assert_noop!(
+				Dpos::report_offence(4, OffenceKind::Equivocation),
+				Error::<Test>::DuplicateOffenceReport
+			);
+
+			// At most `MaxUnappliedSlashes` slashes are deferred in an epoch
+			assert_ok!(Dpos::report_offence(4, OffenceKind::Unresponsiveness));
+			assert_noop!(
+				Dpos::report_offence(3, OffenceKind::Equivocation),
+				Error::<Test>::TooManyUnappliedSlashes
+			);
+		});
+}
+
+#[test]
+fn cancel_deferred_slash_works() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000), (2, 2_000)]).build_and_execute(
+		|| {
+			assert_ok!(Dpos::report_offence(1, OffenceKind::Equivocation));
+			assert_ok!(Dpos::report_offence(2, OffenceKind::Unresponsiveness));
+
+			assert_ok!(Dpos::cancel_deferred_slash(RuntimeOrigin::root(), 0, vec![0]));
+			System::assert_last_event(
+				Event::DeferredSlashCancelled {
+					validator: 1,
+					kind: OffenceKind::Equivocation,
+					epoch_index: 0,
+				}
+				.into(),
+			);
+
+			// Only the remaining slash is applied
+			run_to_epoch(1);
+			assert_eq!(candidate(1).bond, 1_000);
+			assert_eq!(candidate(2).bond, 1_980);
+		},
+	);
+}
+
+#[test]
+fn cancel_deferred_slash_fails() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000), (2, 2_000)]).build_and_execute(
+		|| {
+			assert_ok!(Dpos::report_offence(1, OffenceKind::Equivocation));
+			assert_ok!(Dpos::report_offence(2, OffenceKind::Equivocation));
+
+			assert_noop!(
+				Dpos::cancel_deferred_slash(RuntimeOrigin::signed(1), 0, vec![0]),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				Dpos::cancel_deferred_slash(RuntimeOrigin::root(), 0, vec![]),
+				Error::<Test>::EmptySlashIndices
+			);
+			for slash_indices in [vec![1, 0], vec![0, 0], vec![2], vec![0, 1, 2]] {
+				assert_noop!(
+					Dpos::cancel_deferred_slash(RuntimeOrigin::root(), 0, slash_indices),
+					Error::<Test>::InvalidSlashIndex
+				);
+			}
+		},
+	);
+}
+
+#[test]
+fn epoch_rotation_elects_the_top_active_candidates() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 2_000), (3, 3_000), (4, 4_000)])
+		.build_and_execute(|| {
+			delegate(10, 1, 5_000);
+			assert_ok!(Dpos::leave_candidate_pool(RuntimeOrigin::signed(4)));
+
+			run_to_block(9);
+			assert_eq!(EpochIndex::<Test>::get(), 0);
+			assert_eq!(validator_ids(), vec![4, 3, 2]);
+
+			run_to_block(10);
+			assert_eq!(EpochIndex::<Test>::get(), 1);
+			assert_eq!(
+				CurrentValidators::<Test>::get().into_inner(),
+				vec![(1, 1_000, 6_000), (3, 3_000, 3_000), (2, 2_000, 2_000)]
+			);
+			assert_eq!(ReportedValidators::get(), vec![1, 3, 2]);
+			System::assert_last_event(
+				Event::NextEpochMoved {
+					last_epoch: 0,
+					next_epoch: 1,
+					at_block: 10,
+					total_candidates: 4,
+					total_validators: 3,
+				}
+				.into(),
+			);
+
+			// Only the exposures of the running epoch are kept
+			assert_eq!(EpochExposures::<Test>::iter_prefix(0).count(), 0);
+			assert_eq!(EpochExposures::<Test>::iter_prefix(1).count(), 3);
+		});
+}
+
+#[test]
+fn epoch_exposures_are_paged() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+		delegate(11, 1, 300);
+		delegate(12, 1, 200);
+		run_to_epoch(1);
+
+		assert_eq!(
+			EpochExposures::<Test>::get(1, 1),
+			Some(ExposureOverview {
+				bond: 1_000,
+				commission: Perbill::zero(),
+				total: 2_000,
+				delegator_count: 3,
+				page_count: 2,
+			})
+		);
+		assert_eq!(
+			EpochExposurePages::<Test>::get((1, 1, 0)).into_inner(),
+			vec![(10, 500), (11, 300)]
+		);
+		assert_eq!(EpochExposurePages::<Test>::get((1, 1, 1)).into_inner(), vec![(12, 200)]);
+	});
+}
+
+#[test]
+fn election_runs_over_several_blocks_before_the_boundary() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 2_000), (3, 3_000)])
+		.election_lookahead(3)
+		.build_and_execute(|| {
+			run_to_block(6);
+			assert_eq!(ElectionStatus::<Test>::get(), ElectionPhase::Idle);
+
+			// The candidates are collected and ranked, then the first exposure is captured
+			run_to_block(7);
+			assert_eq!(ElectionStatus::<Test>::get(), ElectionPhase::Exposures { validator: 1 });
+
+			// The remaining exposures of the next epoch are captured in the second page
+			run_to_block(8);
+			assert_eq!(ElectionStatus::<Test>::get(), ElectionPhase::Ready);
+			assert!(EpochExposures::<Test>::get(1, 1).is_some());
+
+			// Changes after the candidates were collected apply from the next election
+			delegate(10, 1, 5_000);
+
+			run_to_block(10);
+			assert_eq!(EpochIndex::<Test>::get(), 1);
+			assert_eq!(ElectionStatus::<Test>::get(), ElectionPhase::Idle);
+			assert_eq!(validator_ids(), vec![3, 2, 1]);
+			assert!(!dpos_events()
+				.iter()
+				.any(|event| matches!(event, Event::ElectionFallback { .. })));
+		});
+}
+
+#[test]
+fn election_falls_back_to_the_boundary_block_when_incomplete() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 2_000), (3, 3_000), (4, 4_000), (5, 5_000)])
+		.election_lookahead(1)
+		.build_and_execute(|| {
+			// A single page can't collect the whole candidate pool
+			run_to_block(9);
+			assert!(ElectionStatus::<Test>::get().is_running());
+
+			run_to_block(10);
+			System::assert_has_event(Event::ElectionFallback { epoch_index: 0 }.into());
+			assert_eq!(ElectionStatus::<Test>::get(), ElectionPhase::Idle);
+			assert_eq!(EpochIndex::<Test>::get(), 1);
+			assert_eq!(validator_ids(), vec![5, 4, 3]);
+		});
+}
+
+#[test]
+fn block_rewards_are_split_with_the_delegators() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+		delegate(11, 1, 300);
+		delegate(12, 1, 200);
+		run_to_epoch(1);
+
+		BlockAuthor::set(Some(1));
+		run_to_block(11);
+
+		assert_eq!(Rewards::<Test>::get(1), 500);
+		assert_eq!(Rewards::<Test>::get(10), 250);
+		assert_eq!(Rewards::<Test>::get(11), 150);
+		assert_eq!(Rewards::<Test>::get(12), 100);
+	});
+}
+
+#[test]
+fn block_rewards_pay_the_commission_and_the_treasury_cut() {
+	ExtBuilder::default().treasury_cut(Perbill::from_percent(10)).build_and_execute(|| {
+		register(1, 1_000, Perbill::from_percent(20));
+		delegate(10, 1, 1_000);
+		run_to_epoch(1);
+
+		BlockAuthor::set(Some(1));
+		run_to_block(11);
+
+		// 100 to the treasury, then 180 of commission and the rest split by stake
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), 100);
+		assert_eq!(Rewards::<Test>::get(1), 540);
+		assert_eq!(Rewards::<Test>::get(10), 360);
+	});
+}
+
+#[test]
+fn block_rewards_skip_authors_outside_the_validator_set() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 2_000), (3, 3_000), (4, 4_000)])
+		.build_and_execute(|| {
+			BlockAuthor::set(Some(1));
+			run_to_block(5);
+			assert_eq!(Rewards::<Test>::get(1), 0);
+		});
+}
+
+#[test]
+fn block_rewards_are_metered_with_the_delegators_of_the_author() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 500);
+		delegate(11, 1, 500);
+		run_to_epoch(1);
+
+		BlockAuthor::set(Some(1));
+		System::set_block_number(11);
+		assert_eq!(
+			Dpos::on_initialize(11),
+			<() as WeightInfo>::on_initialize_block_reward(2) +
+				<() as WeightInfo>::on_initialize_slash_page(0)
+		);
+		assert_eq!(Rewards::<Test>::get(10), 250);
+	});
+}
+
+#[test]
+fn block_rewards_are_compounded() {
+	ExtBuilder::default().genesis_candidates(vec![(1, 1_000)]).build_and_execute(|| {
+		delegate(10, 1, 1_000);
+		assert_ok!(Dpos::set_auto_compound(
+			RuntimeOrigin::signed(10),
+			1,
+			Percent::from_percent(50)
+		));
+		run_to_epoch(1);
+
+		BlockAuthor::set(Some(1));
+		run_to_block(11);
+
+		assert_eq!(Rewards::<Test>::get(1), 500);
+		assert_eq!(Rewards::<Test>::get(10), 250);
+		assert_eq!(delegated(10, 1), 1_250);
+		assert_eq!(candidate(1).sum_delegation, 1_250);
+		assert_eq!(held(HoldReason::DelegateAmountReserved, 10), 1_250);
+		System::assert_has_event(
+			Event::RewardCompounded {
+				candidate_id: 1,
+				delegator: 10,
+				amount: 250,
+				total_delegated_amount: 1_250,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn epoch_budget_is_split_by_authored_blocks() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 2_000)])
+		.reward_mode(RewardMode::EpochBudget)
+		.build_and_execute(|| {
+			BlockAuthor::set(Some(1));
+			run_to_block(5);
+			BlockAuthor::set(Some(2));
+			run_to_block(9);
+			assert_eq!(AuthoredBlocks::<Test>::get(1), 4);
+			assert_eq!(AuthoredBlocks::<Test>::get(2), 4);
+
+			BlockAuthor::set(None);
+			run_to_block(10);
+
+			assert_eq!(Rewards::<Test>::get(1), 5_000);
+			assert_eq!(Rewards::<Test>::get(2), 5_000);
+			assert_eq!(AuthoredBlocks::<Test>::iter().count(), 0);
+			System::assert_has_event(
+				Event::EpochRewardsDistributed {
+					epoch_index: 0,
+					total_blocks: 8,
+					total_reward: EpochRewardBudget::get(),
+				}
+				.into(),
+			);
+		});
+}
+
+#[test]
+fn candidates_are_listed_by_account_id() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(7, 1_000), (3, 3_000), (12, 2_000), (1, 1_500)])
+		.build_and_execute(|| {
+			let candidate_ids: Vec<_> =
+				Dpos::candidates().into_iter().map(|summary| summary.candidate_id).collect();
+			assert_eq!(candidate_ids, vec![1, 3, 7, 12]);
+		});
+}
+
+#[test]
+fn estimate_delegation_reward_accounts_for_the_kicked_delegator() {
+	ExtBuilder::default()
+		.genesis_candidates(vec![(1, 1_000), (2, 1_950)])
+		.genesis_delegations(vec![(10, 1, 100), (11, 1, 200), (12, 1, 300)])
+		.build_and_execute(|| {
+			// The candidate is full, the delegation of 10 is kicked and the stake grows by 300
+			let estimate = Dpos::estimate_delegation_reward(&13, &1, 400).unwrap();
+			assert_eq!(estimate.total_stake, 1_900);
+			assert_eq!(estimate.rank, 2);
+			assert!(estimate.is_active_validator);
+			// Two validators author five blocks each in an epoch
+			assert_eq!(estimate.epoch_reward, Perbill::from_rational(400u128, 1_900) * 5_000);
+
+			delegate(13, 1, 400);
+			assert_eq!(candidate(1).total(), estimate.total_stake);
+			assert_eq!(delegated(10, 1), 0);
+
+			assert_eq!(
+				Dpos::estimate_delegation_reward(&14, &1, 0),
+				Err(Error::<Test>::InvalidZeroAmount.into())
+			);
+		});
+}
+
+#[test]
+fn try_state_catches_a_hold_without_any_record() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Dpos::do_try_state());
+		// The delegator holds stake of the pallet but has never delegated
+		assert_ok!(Balances::hold(&HoldReason::DelegateAmountReserved.into(), &10, 100));
+		assert_eq!(
+			Dpos::do_try_state(),
+			Err("An account holds stake of the pallet without any record".into())
+		);
+	});
+}
@@ -0,0 +1,179 @@
+//! Invariants of the pallet storage, checked by the `try_state` hook.
+//!
+//! The checks read the whole candidate pool and every delegation, so they are only meant for
+//! `try-runtime` and the tests. A failing check names the broken invariant and, where it helps,
+//! stops at the first offending entry.
+use crate::pallet::*;
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{traits::Zero, Saturating, TryRuntimeError},
+	traits::fungible::InspectHold,
+};
+use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+
+/// The stake recorded for an account under each hold reason of the pallet.
+#[derive(Default)]
+struct RecordedHolds<Balance> {
+	bond: Balance,
+	delegated: Balance,
+	profile_deposit: Balance,
+}
+
+impl<T: Config> Pallet<T> {
+	/// Check the invariants of the pallet storage.
+	pub fn do_try_state() -> Result<(), TryRuntimeError> {
+		Self::check_candidate_pool()?;
+		Self::check_delegations()?;
+		Self::check_current_validators()?;
+		Self::check_holds()
+	}
+
+	/// The counted candidate pool agrees with its entries and stays within `MaxCandidates`.
+	fn check_candidate_pool() -> Result<(), TryRuntimeError> {
+		let candidate_count = CandidatePool::<T>::count();
+		ensure!(
+			candidate_count as usize == CandidatePool::<T>::iter_keys().count(),
+			"The candidate pool counter does not match its entries"
+		);
+		ensure!(
+			candidate_count <= T::MaxCandidates::get(),
+			"The candidate pool holds more than `MaxCandidates` candidates"
+		);
+		Ok(())
+	}
+
+	/// The delegators index of each candidate and the delegation records agree in both
+	/// directions, as do the delegated totals and the delegation counts.
+	fn check_delegations() -> Result<(), TryRuntimeError> {
+		let mut delegated_totals = BTreeMap::<T::AccountId, BalanceOf<T>>::new();
+		let mut delegate_counts = BTreeMap::<T::AccountId, u32>::new();
+		for (delegator, candidate_id, delegation) in DelegationInfos::<T>::iter() {
+			ensure!(
+				CandidatePool::<T>::contains_key(&candidate_id),
+				"A delegation targets a candidate outside the candidate pool"
+			);
+			ensure!(
+				CandidateDelegators::<T>::get(&candidate_id)
+					.contains(&delegator, &delegation.amount),
+				"A delegation is missing from the delegators index of its candidate"
+			);
+			delegated_totals
+				.entry(candidate_id)
+				.or_default()
+				.saturating_accrue(delegation.amount);
+			delegate_counts.entry(delegator).or_default().saturating_inc();
+		}
+
+		for (candidate_id, delegators) in CandidateDelegators::<T>::iter() {
+			for (delegator, amount) in delegators.iter() {
+				ensure!(
+					DelegationInfos::<T>::get(delegator, &candidate_id)
+						.is_some_and(|delegation| delegation.amount == *amount),
+					"The delegators index of a candidate lists an unknown delegation"
+				);
+			}
+			let delegators = delegators.into_inner();
+			let unique = delegators.iter().map(|(delegator, _)| delegator).collect::<BTreeSet<_>>();
+			ensure!(
+				unique.len() == delegators.len(),
+				"The delegators index of a candidate lists a delegator twice"
+			);
+		}
+
+		for (candidate_id, candidate) in CandidatePool::<T>::iter() {
+			let delegated = delegated_totals.get(&candidate_id).copied().unwrap_or_else(Zero::zero);
+			ensure!(
+				candidate.sum_delegation == delegated,
+				"The delegated total of a candidate does not match its delegations"
+			);
+		}
+
+		for (delegator, delegate_count) in DelegateCountMap::<T>::iter() {
+			ensure!(
+				delegate_count == delegate_counts.remove(&delegator).unwrap_or_default(),
+				"The delegation count of a delegator does not match its delegations"
+			);
+		}
+		ensure!(delegate_counts.is_empty(), "A delegator has delegations but no delegation count");
+		Ok(())
+	}
+
+	/// Every validator of the active set is a distinct candidate of the pool, leaving or not.
+	/// A validator removed from the pool during the epoch is still recorded by its exposure until
+	/// the epoch ends.
+	fn check_current_validators() -> Result<(), TryRuntimeError> {
+		let epoch_index = EpochIndex::<T>::get();
+		let mut validators = BTreeSet::new();
+		for (validator_id, _, _) in CurrentValidators::<T>::get() {
+			ensure!(
+				CandidatePool::<T>::contains_key(&validator_id) ||
+					EpochExposures::<T>::contains_key(epoch_index, &validator_id),
+				"A validator is neither a candidate nor exposed in the running epoch"
+			);
+			ensure!(
+				validators.insert(validator_id),
+				"A validator is listed twice in the active set"
+			);
+		}
+		Ok(())
+	}
+
+	/// The amounts held under each hold reason of the pallet match the recorded stake: the bond
+	/// and pending bond decrease of a candidate, the delegations and pending undelegations of a
+	/// delegator, and the profile deposit of a candidate. Every account of the system is checked,
+	/// so that a hold left behind without any record is caught as well.
+	fn check_holds() -> Result<(), TryRuntimeError> {
+		let mut recorded = BTreeMap::<T::AccountId, RecordedHolds<BalanceOf<T>>>::new();
+		for (candidate_id, candidate) in CandidatePool::<T>::iter() {
+			recorded.entry(candidate_id).or_default().bond.saturating_accrue(candidate.bond);
+		}
+		for (candidate_id, request) in CandidateBondLessRequests::<T>::iter() {
+			recorded.entry(candidate_id).or_default().bond.saturating_accrue(request.amount);
+		}
+		for (delegator, _, delegation) in DelegationInfos::<T>::iter() {
+			recorded.entry(delegator).or_default().delegated.saturating_accrue(delegation.amount);
+		}
+		for (delegator, requests) in UnbondingDelegations::<T>::iter() {
+			let holds = recorded.entry(delegator).or_default();
+			for request in requests.iter() {
+				holds.delegated.saturating_accrue(request.amount);
+			}
+		}
+		for (candidate_id, (_, deposit)) in CandidateProfiles::<T>::iter() {
+			recorded.entry(candidate_id).or_default().profile_deposit.saturating_accrue(deposit);
+		}
+
+		for who in frame_system::Account::<T>::iter_keys() {
+			let held = |reason: HoldReason| T::NativeBalance::balance_on_hold(&reason.into(), &who);
+			let Some(holds) = recorded.remove(&who) else {
+				ensure!(
+					held(HoldReason::CandidateBondReserved).is_zero() &&
+						held(HoldReason::DelegateAmountReserved).is_zero() &&
+						held(HoldReason::ProfileDeposit).is_zero(),
+					"An account holds stake of the pallet without any record"
+				);
+				continue;
+			};
+			ensure!(
+				held(HoldReason::CandidateBondReserved) == holds.bond,
+				"The bond hold of a candidate does not match its recorded bond"
+			);
+			ensure!(
+				held(HoldReason::DelegateAmountReserved) == holds.delegated,
+				"The delegation hold of a delegator does not match its recorded delegations"
+			);
+			ensure!(
+				held(HoldReason::ProfileDeposit) == holds.profile_deposit,
+				"The profile hold of a candidate does not match its recorded deposit"
+			);
+		}
+		// A record of an account unknown to the system can't be backed by a hold
+		ensure!(
+			recorded.values().all(|holds| {
+				holds.bond.is_zero() && holds.delegated.is_zero() && holds.profile_deposit.is_zero()
+			}),
+			"The recorded stake of an account is not held"
+		);
+		Ok(())
+	}
+}
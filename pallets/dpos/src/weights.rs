@@ -0,0 +1,469 @@
+//! Weights for `pallet_dpos`.
+//!
+//! PLACEHOLDER ESTIMATES, NOT BENCHMARK RESULTS. Every value below is a hand-written conservative
+//! estimate derived from the storage accesses of the call and from the weights of similar calls in
+//! `pallet-parachain-staking`. None of them was measured on reference hardware, which is why the
+//! pallet is still declared in `dev_mode`.
+//!
+//! This file must be replaced by the output of the benchmarks in `benchmarking.rs`, run on the
+//! reference hardware against the runtime built with the `runtime-benchmarks` feature:
+//!
+//! ```sh
+//! frame-omni-bencher v1 benchmark pallet \
+//!     --runtime target/release/wbuild/pba-runtime/pba_runtime.wasm \
+//!     --pallet pallet_dpos --extrinsic "*" \
+//!     --output pallets/dpos/src/weights.rs
+//! ```
+//!
+//! `dev_mode` can only be removed from the pallet once that output is committed.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for `pallet_dpos`.
+pub trait WeightInfo {
+	fn force_report_new_validators(v: u32, ) -> Weight;
+	fn register_as_candidate(c: u32, ) -> Weight;
+	fn delegate(d: u32, ) -> Weight;
+	fn unregister_as_candidate(d: u32, ) -> Weight;
+	fn undelegate(d: u32, ) -> Weight;
+	fn claim_reward() -> Weight;
+	fn leave_candidate_pool() -> Weight;
+	fn execute_leave_candidates(d: u32, ) -> Weight;
+	fn schedule_undelegate(d: u32, ) -> Weight;
+	fn execute_undelegate(u: u32, ) -> Weight;
+	fn cancel_undelegate(d: u32, ) -> Weight;
+	fn candidate_bond_more() -> Weight;
+	fn schedule_candidate_bond_less() -> Weight;
+	fn execute_candidate_bond_less() -> Weight;
+	fn cancel_candidate_bond_less() -> Weight;
+	fn set_auto_compound() -> Weight;
+	fn set_commission() -> Weight;
+	fn set_candidate_profile() -> Weight;
+	fn cancel_deferred_slash(s: u32, ) -> Weight;
+	fn on_initialize_epoch_transition(c: u32, d: u32, ) -> Weight;
+	fn on_initialize_election_page(p: u32, ) -> Weight;
+	fn on_initialize_slash_page(p: u32, ) -> Weight;
+	fn on_initialize_block_reward(d: u32, ) -> Weight;
+	fn on_initialize_epoch_swap(v: u32, d: u32, ) -> Weight;
+}
+
+/// Placeholder weights for `pallet_dpos`, see the module documentation.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Placeholder estimate, not measured.
+	/// The range of component `v` is `[0, 99]`.
+	fn force_report_new_validators(v: u32, ) -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(Weight::from_parts(120_000, 0).saturating_mul(v.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `c` is `[0, 199]`.
+	fn register_as_candidate(c: u32, ) -> Weight {
+		Weight::from_parts(48_000_000, 4764)
+			.saturating_add(Weight::from_parts(20_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[0, 300]`.
+	fn delegate(d: u32, ) -> Weight {
+		Weight::from_parts(92_000_000, 6196)
+			.saturating_add(Weight::from_parts(160_000, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[0, 300]`.
+	fn unregister_as_candidate(d: u32, ) -> Weight {
+		Weight::from_parts(70_000_000, 4764)
+			.saturating_add(Weight::from_parts(42_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(d.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[1, 300]`.
+	fn undelegate(d: u32, ) -> Weight {
+		Weight::from_parts(64_000_000, 4764)
+			.saturating_add(Weight::from_parts(150_000, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	fn claim_reward() -> Weight {
+		Weight::from_parts(38_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn leave_candidate_pool() -> Weight {
+		Weight::from_parts(18_000_000, 3581)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[0, 300]`.
+	fn execute_leave_candidates(d: u32, ) -> Weight {
+		Weight::from_parts(72_000_000, 4764)
+			.saturating_add(Weight::from_parts(42_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(d.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[1, 300]`.
+	fn schedule_undelegate(d: u32, ) -> Weight {
+		Weight::from_parts(52_000_000, 5390)
+			.saturating_add(Weight::from_parts(150_000, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `u` is `[1, 30]`.
+	fn execute_undelegate(u: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 5390)
+			.saturating_add(Weight::from_parts(9_000_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(u.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[1, 300]`.
+	fn cancel_undelegate(d: u32, ) -> Weight {
+		Weight::from_parts(50_000_000, 5390)
+			.saturating_add(Weight::from_parts(160_000, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	fn candidate_bond_more() -> Weight {
+		Weight::from_parts(46_000_000, 4764)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn schedule_candidate_bond_less() -> Weight {
+		Weight::from_parts(24_000_000, 3581)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn execute_candidate_bond_less() -> Weight {
+		Weight::from_parts(44_000_000, 4764)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn cancel_candidate_bond_less() -> Weight {
+		Weight::from_parts(22_000_000, 3581)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn set_auto_compound() -> Weight {
+		Weight::from_parts(17_000_000, 3531)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn set_commission() -> Weight {
+		Weight::from_parts(18_000_000, 3581)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn set_candidate_profile() -> Weight {
+		Weight::from_parts(52_000_000, 4764)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `s` is `[1, 100]`.
+	fn cancel_deferred_slash(s: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 3615)
+			.saturating_add(Weight::from_parts(6_000_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 14_516).saturating_mul(s.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `c` is `[1, 200]`.
+	/// The range of component `d` is `[0, 300]`.
+	fn on_initialize_epoch_transition(c: u32, d: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 8014)
+			.saturating_add(Weight::from_parts(60_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(250_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 4800).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `p` is `[1, 4000]`.
+	fn on_initialize_election_page(p: u32, ) -> Weight {
+		Weight::from_parts(28_000_000, 25820)
+			.saturating_add(Weight::from_parts(2_400_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 112).saturating_mul(p.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `p` is `[1, 500]`.
+	fn on_initialize_slash_page(p: u32, ) -> Weight {
+		Weight::from_parts(22_000_000, 14516)
+			.saturating_add(Weight::from_parts(45_000_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 5206).saturating_mul(p.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[0, 300]`.
+	fn on_initialize_block_reward(d: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 7820)
+			.saturating_add(Weight::from_parts(55_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes((6_u64).saturating_mul(d.into())))
+			.saturating_add(Weight::from_parts(0, 5206).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `v` is `[1, 100]`.
+	/// The range of component `d` is `[0, 300]`.
+	fn on_initialize_epoch_swap(v: u32, d: u32, ) -> Weight {
+		Weight::from_parts(36_000_000, 9860)
+			.saturating_add(Weight::from_parts(2_500_000, 0).saturating_mul(v.into()))
+			.saturating_add(Weight::from_parts(180_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(v.into()))
+			.saturating_add(Weight::from_parts(0, 11200).saturating_mul(d.into()))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Placeholder estimate, not measured.
+	/// The range of component `v` is `[0, 99]`.
+	fn force_report_new_validators(v: u32, ) -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(Weight::from_parts(120_000, 0).saturating_mul(v.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `c` is `[0, 199]`.
+	fn register_as_candidate(c: u32, ) -> Weight {
+		Weight::from_parts(48_000_000, 4764)
+			.saturating_add(Weight::from_parts(20_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[0, 300]`.
+	fn delegate(d: u32, ) -> Weight {
+		Weight::from_parts(92_000_000, 6196)
+			.saturating_add(Weight::from_parts(160_000, 0).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[0, 300]`.
+	fn unregister_as_candidate(d: u32, ) -> Weight {
+		Weight::from_parts(70_000_000, 4764)
+			.saturating_add(Weight::from_parts(42_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(d.into())))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(d.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[1, 300]`.
+	fn undelegate(d: u32, ) -> Weight {
+		Weight::from_parts(64_000_000, 4764)
+			.saturating_add(Weight::from_parts(150_000, 0).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	fn claim_reward() -> Weight {
+		Weight::from_parts(38_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn leave_candidate_pool() -> Weight {
+		Weight::from_parts(18_000_000, 3581)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[0, 300]`.
+	fn execute_leave_candidates(d: u32, ) -> Weight {
+		Weight::from_parts(72_000_000, 4764)
+			.saturating_add(Weight::from_parts(42_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(d.into())))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(d.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[1, 300]`.
+	fn schedule_undelegate(d: u32, ) -> Weight {
+		Weight::from_parts(52_000_000, 5390)
+			.saturating_add(Weight::from_parts(150_000, 0).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `u` is `[1, 30]`.
+	fn execute_undelegate(u: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 5390)
+			.saturating_add(Weight::from_parts(9_000_000, 0).saturating_mul(u.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(u.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[1, 300]`.
+	fn cancel_undelegate(d: u32, ) -> Weight {
+		Weight::from_parts(50_000_000, 5390)
+			.saturating_add(Weight::from_parts(160_000, 0).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+			.saturating_add(Weight::from_parts(0, 56).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	fn candidate_bond_more() -> Weight {
+		Weight::from_parts(46_000_000, 4764)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn schedule_candidate_bond_less() -> Weight {
+		Weight::from_parts(24_000_000, 3581)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn execute_candidate_bond_less() -> Weight {
+		Weight::from_parts(44_000_000, 4764)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn cancel_candidate_bond_less() -> Weight {
+		Weight::from_parts(22_000_000, 3581)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn set_auto_compound() -> Weight {
+		Weight::from_parts(17_000_000, 3531)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn set_commission() -> Weight {
+		Weight::from_parts(18_000_000, 3581)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Placeholder estimate, not measured.
+	fn set_candidate_profile() -> Weight {
+		Weight::from_parts(52_000_000, 4764)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `s` is `[1, 100]`.
+	fn cancel_deferred_slash(s: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 3615)
+			.saturating_add(Weight::from_parts(6_000_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 14_516).saturating_mul(s.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `c` is `[1, 200]`.
+	/// The range of component `d` is `[0, 300]`.
+	fn on_initialize_epoch_transition(c: u32, d: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 8014)
+			.saturating_add(Weight::from_parts(60_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(250_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 4800).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `p` is `[1, 4000]`.
+	fn on_initialize_election_page(p: u32, ) -> Weight {
+		Weight::from_parts(28_000_000, 25820)
+			.saturating_add(Weight::from_parts(2_400_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 112).saturating_mul(p.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `p` is `[1, 500]`.
+	fn on_initialize_slash_page(p: u32, ) -> Weight {
+		Weight::from_parts(22_000_000, 14516)
+			.saturating_add(Weight::from_parts(45_000_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 5206).saturating_mul(p.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `d` is `[0, 300]`.
+	fn on_initialize_block_reward(d: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 7820)
+			.saturating_add(Weight::from_parts(55_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(d.into())))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes((6_u64).saturating_mul(d.into())))
+			.saturating_add(Weight::from_parts(0, 5206).saturating_mul(d.into()))
+	}
+	/// Placeholder estimate, not measured.
+	/// The range of component `v` is `[1, 100]`.
+	/// The range of component `d` is `[0, 300]`.
+	fn on_initialize_epoch_swap(v: u32, d: u32, ) -> Weight {
+		Weight::from_parts(36_000_000, 9860)
+			.saturating_add(Weight::from_parts(2_500_000, 0).saturating_mul(v.into()))
+			.saturating_add(Weight::from_parts(180_000_000, 0).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(v.into()))
+			.saturating_add(Weight::from_parts(0, 11200).saturating_mul(d.into()))
+	}
+}
@@ -8,6 +8,7 @@ use frame::{
 			runtime,
 			traits::AsEnsureOriginWithArg,
 			weights::FixedFee,
+			PalletId,
 		},
 	},
 	prelude::*,
@@ -20,14 +21,18 @@ use frame::{
 	},
 	traits::{FindAuthor, One},
 };
+use pallet_dpos_runtime_api::{
+	CandidateSummary, DelegationRewardEstimate, DelegationSummary, ValidatorSetSummary,
+};
 use pallet_transaction_payment::{ConstFeeMultiplier, FeeDetails, Multiplier, RuntimeDispatchInfo};
+use sp_runtime::{traits::AccountIdConversion, Perbill};
 
 #[runtime_version]
 const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("pba-assignment-template"),
 	impl_name: create_runtime_str!("pba-assignment-template"),
 	authoring_version: 1,
-	spec_version: 0,
+	spec_version: 1,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -121,7 +126,9 @@ parameter_types! {
 
 #[derive_impl(pallet_transaction_payment::config_preludes::TestDefaultConfig)]
 impl pallet_transaction_payment::Config for Runtime {
-	type OnChargeTransaction = pallet_transaction_payment::FungibleAdapter<Balances, ()>;
+	// Fees and tips go to the reward pot of the block author, minus the treasury cut
+	type OnChargeTransaction =
+		pallet_transaction_payment::FungibleAdapter<Balances, pallet_dpos::DealWithFees<Runtime>>;
 	type OperationalFeeMultiplier = ConstU8<5>;
 	type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
 	// These two define what the transaction fee would.
@@ -176,6 +183,30 @@ parameter_types! {
 	pub const MaxDelegateCount : u32 = 30;
 	pub const EpochDuration : u32 = EPOCH_DURATION;
 	pub const MinDelegateAmount : u128 = 150;
+	pub const UnbondingEpochs : u32 = 2;
+	pub const EquivocationSlashFraction : Perbill = Perbill::from_percent(10);
+	pub const UnresponsivenessSlashFraction : Perbill = Perbill::from_percent(1);
+	pub const SlashDestination : Option<AccountId> = None;
+	pub const SlashDeferEpochs : u32 = 1;
+	pub const MaxUnappliedSlashes : u32 = 100;
+	// The page sizes and the block share are not derived from measured weights yet. They must
+	// be re-derived once `pallet_dpos::weights` holds benchmarked weights, so that the worst
+	// case checked by the `integrity_test` of the pallet fits the block share.
+	pub const SlashPageSize : u32 = 500;
+	pub const EpochTransitionBlockShare : Perbill = Perbill::from_percent(50);
+	pub const ElectionLookahead : u32 = 10;
+	pub const ElectionPageSize : u32 = 4_000;
+	pub const MaxExposurePageSize : u32 = 64;
+	pub const MaxCommission : Perbill = Perbill::from_percent(50);
+	pub const CommissionUpdateEpochs : u32 = 1;
+	pub const BlockRewardRate : Perbill = Perbill::from_percent(5);
+	pub const RewardMode : pallet_dpos::RewardMode = pallet_dpos::RewardMode::PerBlock;
+	pub const EpochRewardBudget : Balance = 0;
+	pub const TreasuryCut : Perbill = Perbill::from_percent(10);
+	pub TreasuryAccount : AccountId = PalletId(*b"py/trsry").into_account_truncating();
+	pub const DposPalletId : PalletId = PalletId(*b"py/dpos_");
+	pub const MaxProfileFieldLength : u32 = 128;
+	pub const ProfileDepositPerByte : Balance = 1;
 }
 
 /// The authorship implementation for the runtime.
@@ -190,7 +221,7 @@ impl FindAuthor<AccountId> for RoundRobinAuthor {
 		// Get the active validators
 		let active_validator_ids = ValidatorSet::get();
 		// If there are no active validators, return None
-		if active_validator_ids.len() == 0 {
+		if active_validator_ids.is_empty() {
 			return None;
 		}
 		// Return the validator based on the block number
@@ -224,11 +255,34 @@ impl pallet_dpos::Config for Runtime {
 	type ReportNewValidatorSet = StoreNewValidatorSet;
 	type RuntimeHoldReason = RuntimeHoldReason;
 	type MaxDelegateCount = MaxDelegateCount;
+	type UnbondingEpochs = UnbondingEpochs;
 	type EpochDuration = EpochDuration;
 	type MinCandidateBond = MinCandidateBond;
 	type MinDelegateAmount = MinDelegateAmount;
 	type FindAuthor = RoundRobinAuthor;
+	type WeightInfo = pallet_dpos::weights::SubstrateWeight<Runtime>;
 	type ForceOrigin = EnsureRoot<AccountId>;
+	type EquivocationSlashFraction = EquivocationSlashFraction;
+	type UnresponsivenessSlashFraction = UnresponsivenessSlashFraction;
+	type SlashDestination = SlashDestination;
+	type SlashDeferEpochs = SlashDeferEpochs;
+	type MaxUnappliedSlashes = MaxUnappliedSlashes;
+	type SlashPageSize = SlashPageSize;
+	type EpochTransitionBlockShare = EpochTransitionBlockShare;
+	type ElectionLookahead = ElectionLookahead;
+	type ElectionPageSize = ElectionPageSize;
+	type MaxExposurePageSize = MaxExposurePageSize;
+	type MaxCommission = MaxCommission;
+	type CommissionUpdateEpochs = CommissionUpdateEpochs;
+	// Mint 5% of the stake backing the block author on every block
+	type RewardCurve = pallet_dpos::StakePercentage<BlockRewardRate>;
+	type RewardMode = RewardMode;
+	type EpochRewardBudget = EpochRewardBudget;
+	type TreasuryCut = TreasuryCut;
+	type TreasuryAccount = TreasuryAccount;
+	type PalletId = DposPalletId;
+	type MaxProfileFieldLength = MaxProfileFieldLength;
+	type ProfileDepositPerByte = ProfileDepositPerByte;
 }
 
 /// The signed extensions that are added to the runtime.
@@ -252,8 +306,18 @@ type Nonce = <Runtime as frame_system::Config>::Nonce;
 type Block = frame::runtime::types_common::BlockOf<Runtime, SignedExtra>;
 type Header = HeaderFor<Runtime>;
 
-type RuntimeExecutive =
-	Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPalletsWithSystem>;
+/// The migrations to run at the next runtime upgrade. Each one is versioned and only runs if the
+/// on-chain storage version of its pallet matches.
+type Migrations = (pallet_dpos::migrations::v1::MigrateV0ToV1<Runtime>,);
+
+type RuntimeExecutive = Executive<
+	Runtime,
+	Block,
+	frame_system::ChainContext<Runtime>,
+	Runtime,
+	AllPalletsWithSystem,
+	Migrations,
+>;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benches {
@@ -392,6 +456,36 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_dpos_runtime_api::DposApi<Block, AccountId, Balance> for Runtime {
+		fn candidates() -> Vec<CandidateSummary<AccountId, Balance>> {
+			Dpos::candidates()
+		}
+
+		fn delegations(delegator: AccountId) -> Vec<DelegationSummary<AccountId, Balance>> {
+			Dpos::delegations(&delegator)
+		}
+
+		fn pending_rewards(account: AccountId) -> Balance {
+			Dpos::pending_rewards(&account)
+		}
+
+		fn current_validator_set() -> ValidatorSetSummary<AccountId, Balance> {
+			Dpos::current_validator_set()
+		}
+
+		fn next_validator_set() -> ValidatorSetSummary<AccountId, Balance> {
+			Dpos::next_validator_set()
+		}
+
+		fn estimate_delegation_reward(
+			delegator: AccountId,
+			candidate: AccountId,
+			amount: Balance,
+		) -> Result<DelegationRewardEstimate<Balance>, sp_runtime::DispatchError> {
+			Dpos::estimate_delegation_reward(&delegator, &candidate, amount)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame::deps::frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -469,3 +563,13 @@ impl_runtime_apis! {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dpos_epoch_boundary_fits_its_block_share_with_the_runtime_weights() {
+		<Dpos as Hooks<BlockNumberFor<Runtime>>>::integrity_test();
+	}
+}